@@ -0,0 +1,140 @@
+use crate::{BuildHasherExt, HasherExt};
+use std::{hash::Hash, marker::PhantomData};
+
+/// A Bloom filter, an approximate membership structure which never gives false negatives.
+///
+/// The `k` bit positions of an item are the first `k` values of the sequence of hash values
+/// of the item, so each operation builds a single hasher and hashes the item only once,
+/// instead of building `k` independent hashers.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+///
+/// let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+/// let mut filter = BloomFilter::new(builder, 1024, 7);
+///
+/// filter.insert("Hello world!");
+/// assert!(filter.contains("Hello world!"));
+///```
+pub struct BloomFilter<T: ?Sized, B> {
+    builder: B,
+    m_bits: usize,
+    k: usize,
+    words: Vec<u64>,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<T, B> BloomFilter<T, B>
+where
+    T: Hash + ?Sized,
+    B: BuildHasherExt,
+    B::Hasher: HasherExt,
+{
+    /// Creates a new filter with `m_bits` bits, setting `k` bits for each item.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of `m_bits` or `k` is zero.
+    pub fn new(builder: B, m_bits: usize, k: usize) -> Self {
+        assert!(m_bits > 0, "the filter must have at least one bit");
+        assert!(k > 0, "at least one hash value is required");
+
+        Self {
+            builder,
+            m_bits,
+            k,
+            words: vec![0; m_bits.div_ceil(64)],
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of bits of the filter.
+    pub fn m_bits(&self) -> usize {
+        self.m_bits
+    }
+
+    /// Returns the number of bits set for each item.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Inserts an item into the filter.
+    pub fn insert(&mut self, item: &T) {
+        for h in self.builder.hashes_one(item).take(self.k) {
+            let index = (u64::from(h) % self.m_bits as u64) as usize;
+            self.words[index / 64] |= 1 << (index % 64);
+        }
+    }
+
+    /// Returns `true` if the item may be in the filter, and `false` if it is definitely not.
+    /// The check stops at the first bit which is not set.
+    pub fn contains(&self, item: &T) -> bool {
+        self.builder.hashes_one(item).take(self.k).all(|h| {
+            let index = (u64::from(h) % self.m_bits as u64) as usize;
+            self.words[index / 64] & 1 << (index % 64) != 0
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{build_sip_hasher::BuildSipHasher, BuildPairHasher};
+    use std::{cell::Cell, hash::BuildHasher};
+
+    /// A builder which counts how many hashers it has built.
+    struct CountingBuilder<B> {
+        inner: B,
+        built: Cell<usize>,
+    }
+
+    impl<B: BuildHasher> BuildHasher for CountingBuilder<B> {
+        type Hasher = B::Hasher;
+
+        fn build_hasher(&self) -> Self::Hasher {
+            self.built.set(self.built.get() + 1);
+            self.inner.build_hasher()
+        }
+    }
+
+    fn builder() -> BuildPairHasher<BuildSipHasher, BuildSipHasher> {
+        BuildPairHasher::new_with_keys((0, 0), (1, 1))
+    }
+
+    #[test]
+    fn no_false_negatives() {
+        let mut filter = BloomFilter::new(builder(), 1 << 14, 7);
+        const ITEMS: u64 = 1000;
+
+        for item in 0..ITEMS {
+            filter.insert(&item);
+        }
+
+        assert!((0..ITEMS).all(|item| filter.contains(&item)));
+
+        let false_positives = (ITEMS..2 * ITEMS)
+            .filter(|item| filter.contains(item))
+            .count();
+        assert!(false_positives < 10);
+    }
+
+    #[test]
+    fn one_hasher_per_operation() {
+        let counting = CountingBuilder {
+            inner: builder(),
+            built: Cell::new(0),
+        };
+        let mut filter = BloomFilter::new(counting, 1024, 7);
+
+        filter.insert("Hello world!");
+        assert_eq!(filter.builder.built.get(), 1);
+
+        assert!(filter.contains("Hello world!"));
+        assert_eq!(filter.builder.built.get(), 2);
+
+        filter.contains("Goodbye world!");
+        assert_eq!(filter.builder.built.get(), 3);
+    }
+}
@@ -0,0 +1,97 @@
+use std::hash::BuildHasher;
+
+use crate::{
+    build_sip_hasher::{BuildSipHasher, SipHasherKeys},
+    multi_hasher::MultiHasher,
+};
+
+/// An instance of [`BuildHasher`] trait which builds [`MultiHasher`] instances from `N` builders.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+///
+/// let builder = BuildMultiHasher::new_with_keys([(0, 0), (1, 1), (2, 2), (3, 3)]);
+///
+/// const HASHE_COUNT: usize = 10;
+/// let hashes = builder
+///     .hashes_one("Hello world!")
+///     .take(HASHE_COUNT)
+///     .collect::<Vec<_>>();
+/// assert_eq!(hashes.len(), HASHE_COUNT)
+///```
+pub struct BuildMultiHasher<B, const N: usize> {
+    builders: [B; N],
+}
+
+impl<B, const N: usize> BuildMultiHasher<B, N> {
+    /// Creates a new builder from `N` builders.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    pub fn new(builders: [B; N]) -> Self {
+        assert!(N > 0, "at least one builder is required");
+        Self { builders }
+    }
+}
+
+impl<const N: usize> BuildMultiHasher<BuildSipHasher, N> {
+    pub fn new_with_keys(keys: [SipHasherKeys; N]) -> Self {
+        Self::new(keys.map(BuildSipHasher::from))
+    }
+}
+
+impl<B, const N: usize> BuildHasher for BuildMultiHasher<B, N>
+where
+    B: BuildHasher,
+{
+    type Hasher = MultiHasher<B::Hasher, N>;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        MultiHasher::new(
+            self.builders
+                .each_ref()
+                .map(|builder| builder.build_hasher()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildHasherExt, BuildPairHasher, KirschMitzenmacher};
+
+    #[test]
+    fn hashes_eq() {
+        let builder = BuildMultiHasher::new_with_keys([(0, 0), (1, 1), (2, 2)]);
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        let hashes1 = builder
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+        let hashes2 = builder
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        assert_eq!(hashes1, hashes2);
+    }
+
+    #[test]
+    fn two_hashers_match_pair_hasher() {
+        let multi = BuildMultiHasher::new_with_keys([(0, 0), (1, 1)]);
+        let pair = BuildPairHasher::new_with_keys((0, 0), (1, 1)).with_sequence(KirschMitzenmacher);
+        let item = "Hello world!";
+
+        // Two hashers give plain double hashing.
+        let hashes1 = multi.hashes_one(item).take(10).collect::<Vec<_>>();
+        let hashes2 = pair.hashes_one(item).take(10).collect::<Vec<_>>();
+        assert_eq!(hashes1, hashes2);
+
+        assert_eq!(multi.hash_one(item), pair.hash_one(item));
+    }
+}
@@ -1,10 +1,13 @@
-use std::hash::BuildHasher;
+use std::hash::{BuildHasher, Hash, Hasher};
 
+#[cfg(feature = "rand")]
 use rand::rngs::ThreadRng;
 
 use crate::{
     build_sip_hasher::{BuildSipHasher, SipHasherKeys},
     pair_hasher::PairHasher,
+    splitmix::SplitMix64,
+    BuildHasherExt, DefaultSequence, Hash64, HashSeq, HashSequence,
 };
 
 /// An instance of [`BuildHasher`] trait which builds [PairHasher] instances.
@@ -28,14 +31,54 @@ use crate::{
 ///     .collect::<Vec<_>>();
 /// assert_eq!(hashes.len(), HASHE_COUNT)
 ///```
-pub struct BuildPairHasher<B1, B2> {
+pub struct BuildPairHasher<B1, B2, S = DefaultSequence> {
     builder1: B1,
     builder2: B2,
+    mix_seed: u64,
+    sequence: S,
+    seed: Option<u64>,
 }
 
 impl<B1, B2> BuildPairHasher<B1, B2> {
     pub fn new(builder1: B1, builder2: B2) -> Self {
-        Self { builder1, builder2 }
+        Self {
+            builder1,
+            builder2,
+            mix_seed: 0,
+            sequence: DefaultSequence,
+            seed: None,
+        }
+    }
+}
+
+impl<B1, B2, S> BuildPairHasher<B1, B2, S> {
+    /// Sets the mixing seed, which is folded into the initial state of the recurrence used
+    /// to generate the sequence of hash values. It changes the generated sequences without
+    /// a third hasher. The default mixing seed is 0.
+    pub fn with_mix_seed(self, seed: u64) -> Self {
+        Self {
+            mix_seed: seed,
+            ..self
+        }
+    }
+
+    /// Sets the [`HashSequence`] strategy which turns the two base hashes into the sequence of
+    /// hash values. The default strategy is [`DefaultSequence`].
+    pub fn with_sequence<T>(self, sequence: T) -> BuildPairHasher<B1, B2, T> {
+        BuildPairHasher {
+            builder1: self.builder1,
+            builder2: self.builder2,
+            mix_seed: self.mix_seed,
+            sequence,
+            seed: self.seed,
+        }
+    }
+
+    /// Returns the seed the keys of the builder were derived from by [`BuildPairHasher::reseeded`],
+    /// or `None` if the builder was created with explicit keys or derived in another way,
+    /// e.g. by [`BuildPairHasher::for_shard`].
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
     }
 }
 
@@ -46,6 +89,35 @@ impl BuildPairHasher<BuildSipHasher, BuildSipHasher> {
         Self::new(builder1, builder2)
     }
 
+    /// Creates a new builder from four key words. The first two words are the keys
+    /// of the first hasher, the last two are the keys of the second hasher.
+    pub fn new_with_key_words(words: [u64; 4]) -> Self {
+        Self::new_with_keys((words[0], words[1]), (words[2], words[3]))
+    }
+
+    /// Generates the sequence of hash values for a slice of bytes in a way which can be
+    /// reproduced by any SipHash-2-4 implementation, e.g. in another language.
+    ///
+    /// The bytes are fed once into each of the two SipHash-2-4 hashers, keyed with the keys of
+    /// the builder, without any framing (no length prefix, no terminator). With `a` and `b` the
+    /// two 64-bit SipHash outputs and `s` the mixing seed, all arithmetic wrapping modulo `2^64`,
+    /// the sequence is:
+    ///
+    /// ```text
+    /// e[0] = a + (s >> 62),  b[0] = b | 1,  c = 4 * s
+    /// e[i + 1] = e[i] + b[i]
+    /// b[i + 1] = b[i] + c
+    /// ```
+    ///
+    /// so, with a mixing seed below `2^62`, the first two values are `a` and `a + (b | 1)`, while
+    /// [`Hasher::finish`] returns `a + b`.
+    ///
+    /// It is the same sequence as [`BuildHasherExt::hashes_bytes`], to which it delegates.
+    pub fn hashes_bytes_compat<'a>(&'a self, bytes: &'a [u8]) -> impl Iterator<Item = Hash64> + 'a {
+        self.hashes_bytes(bytes)
+    }
+
+    #[cfg(feature = "rand")]
     pub fn new_with_rng(rng: ThreadRng) -> Self {
         let builder1 = BuildSipHasher::from(rng.clone());
         let builder2 = BuildSipHasher::from(rng);
@@ -53,24 +125,118 @@ impl BuildPairHasher<BuildSipHasher, BuildSipHasher> {
     }
 }
 
-impl<B1, B2> BuildHasher for BuildPairHasher<B1, B2>
+impl<S> BuildPairHasher<BuildSipHasher, BuildSipHasher, S> {
+    /// Returns a new builder with keys derived from the `seed`, using SplitMix64 to expand
+    /// the seed into the four key words. The same seed always produces the same builder.
+    /// The mixing seed and the sequence strategy are kept, and the seed is returned by
+    /// [`BuildPairHasher::seed`].
+    pub fn reseeded(self, seed: u64) -> Self {
+        let mut rng = SplitMix64::new(seed);
+        let words = [(); 4].map(|_| rng.next_u64());
+        let builder = BuildPairHasher::new_with_key_words(words)
+            .with_mix_seed(self.mix_seed)
+            .with_sequence(self.sequence);
+
+        Self {
+            seed: Some(seed),
+            ..builder
+        }
+    }
+}
+
+impl<S: Clone> BuildPairHasher<BuildSipHasher, BuildSipHasher, S> {
+    /// Returns a builder for the shard `shard_id`, e.g. a worker thread, whose keys are derived
+    /// from the keys of this builder and the `shard_id`. Different shards get independent
+    /// sequences, while the same shard always gets the same sequences. The mixing seed
+    /// and the sequence strategy are kept.
+    pub fn for_shard(&self, shard_id: u64) -> Self {
+        let (key0, key1) = self.builder1.keys();
+        let (key2, key3) = self.builder2.keys();
+
+        let mut rng = SplitMix64::new(shard_id);
+        let words = [key0, key1, key2, key3].map(|key| key ^ rng.next_u64());
+        BuildPairHasher::new_with_key_words(words)
+            .with_mix_seed(self.mix_seed)
+            .with_sequence(self.sequence.clone())
+    }
+}
+
+/// A [`BuildPairHasher`] which combines two SipHash hashers keyed with the fixed keys `(0, 0)`
+/// and `(1, 1)`. It implements [`Default`], so it can be used with generic containers which
+/// require `S: BuildHasher + Default`, e.g. `HashMap<K, V, DefaultPairHasher>`.
+///
+/// NB: Because the keys are fixed and public, the hash values are predictable, so the hasher
+/// does not protect a map against inputs crafted to collide.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+/// use std::collections::HashMap;
+///
+/// let mut map: HashMap<&str, u32, DefaultPairHasher> = HashMap::default();
+/// map.insert("one", 1);
+/// assert_eq!(map.get("one"), Some(&1));
+///```
+pub type DefaultPairHasher = BuildPairHasher<BuildSipHasher, BuildSipHasher>;
+
+impl Default for DefaultPairHasher {
+    fn default() -> Self {
+        Self::new_with_keys((0, 0), (1, 1))
+    }
+}
+
+impl<B1, B2, S> BuildPairHasher<B1, B2, S>
 where
     B1: BuildHasher,
     B2: BuildHasher,
+    S: HashSequence + Clone,
 {
-    type Hasher = PairHasher<B1::Hasher, B2::Hasher>;
+    /// Builds a [PairHasher] whose two hashers already have the `extra_seed` written into them.
+    /// It allows domain separation between different calls without building a new builder.
+    pub fn build_hasher_seeded(&self, extra_seed: u64) -> PairHasher<B1::Hasher, B2::Hasher, S> {
+        let mut hasher1 = self.builder1.build_hasher();
+        let mut hasher2 = self.builder2.build_hasher();
+
+        hasher1.write_u64(extra_seed);
+        hasher2.write_u64(extra_seed);
+
+        PairHasher::new(hasher1, hasher2)
+            .with_mix_seed(self.mix_seed)
+            .with_sequence(self.sequence.clone())
+    }
+
+    /// Hashes the item once and returns its [`HashSeq`], whose sequence of hash values can be
+    /// iterated any number of times, e.g. to insert the item into several filters.
+    pub fn hash_seq<T: Hash>(&self, item: T) -> HashSeq<S> {
+        let mut hasher = self.build_hasher();
+
+        item.hash(&mut hasher);
+        hasher.finish_seq()
+    }
+}
+
+impl<B1, B2, S> BuildHasher for BuildPairHasher<B1, B2, S>
+where
+    B1: BuildHasher,
+    B2: BuildHasher,
+    S: HashSequence + Clone,
+{
+    type Hasher = PairHasher<B1::Hasher, B2::Hasher, S>;
 
     fn build_hasher(&self) -> Self::Hasher {
         let hasher1 = self.builder1.build_hasher();
         let hasher2 = self.builder2.build_hasher();
         PairHasher::new(hasher1, hasher2)
+            .with_mix_seed(self.mix_seed)
+            .with_sequence(self.sequence.clone())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{BuildHasherExt, Hash64, HasherExt};
+    use crate::{HasherExt, KirschMitzenmacher};
     use std::hash::{BuildHasher, Hash};
 
     #[test]
@@ -122,4 +288,256 @@ mod tests {
 
         assert_eq!(hashes1, hashes2)
     }
+
+    #[test]
+    fn build_hasher_seeded() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        let mut hasher1 = builder.build_hasher_seeded(1);
+        item.hash(&mut hasher1);
+        let hashes1 = hasher1.finish_iter().take(HASH_COUNT).collect::<Vec<_>>();
+
+        let mut hasher2 = builder.build_hasher_seeded(2);
+        item.hash(&mut hasher2);
+        let hashes2 = hasher2.finish_iter().take(HASH_COUNT).collect::<Vec<_>>();
+
+        let mut hasher3 = builder.build_hasher_seeded(1);
+        item.hash(&mut hasher3);
+        let hashes3 = hasher3.finish_iter().take(HASH_COUNT).collect::<Vec<_>>();
+
+        assert_ne!(hashes1, hashes2);
+        assert_eq!(hashes1, hashes3);
+    }
+
+    #[test]
+    fn second_keys_independence() {
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        let hashes1 = BuildPairHasher::new_with_keys((0, 0), (1, 1))
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        let hashes2 = BuildPairHasher::new_with_keys((0, 0), (2, 2))
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        // The first value is derived only from the first hasher, the rest depend on the second one.
+        assert_eq!(hashes1[0], hashes2[0]);
+        assert_ne!(hashes1, hashes2);
+        assert!(hashes1[1..]
+            .iter()
+            .zip(&hashes2[1..])
+            .all(|(h1, h2)| h1 != h2));
+    }
+
+    #[test]
+    fn new_with_key_words() {
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        let hashes1 = BuildPairHasher::new_with_key_words([1, 2, 3, 4])
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        let hashes2 = BuildPairHasher::new_with_keys((1, 2), (3, 4))
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        assert_eq!(hashes1, hashes2)
+    }
+
+    #[test]
+    fn reseeded() {
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        let hashes = |builder: BuildPairHasher<BuildSipHasher, BuildSipHasher>| {
+            builder
+                .hashes_one(item)
+                .take(HASH_COUNT)
+                .collect::<Vec<_>>()
+        };
+
+        let original = hashes(BuildPairHasher::new_with_keys((0, 0), (1, 1)));
+        let reseeded1 = hashes(BuildPairHasher::new_with_keys((0, 0), (1, 1)).reseeded(42));
+        let reseeded2 = hashes(BuildPairHasher::new_with_keys((2, 2), (3, 3)).reseeded(42));
+        let reseeded3 = hashes(BuildPairHasher::new_with_keys((0, 0), (1, 1)).reseeded(43));
+
+        assert_ne!(original, reseeded1);
+        assert_eq!(reseeded1, reseeded2);
+        assert_ne!(reseeded1, reseeded3);
+
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        assert_eq!(builder.seed(), None);
+
+        let builder = builder.reseeded(42);
+        assert_eq!(builder.seed(), Some(42));
+        assert_eq!(builder.reseeded(43).seed(), Some(43));
+
+        // The seed is kept by the transforms which keep the keys.
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1))
+            .reseeded(42)
+            .with_mix_seed(7)
+            .with_sequence(KirschMitzenmacher);
+        assert_eq!(builder.seed(), Some(42));
+        assert_eq!(builder.for_shard(3).seed(), None);
+    }
+
+    #[test]
+    fn for_shard() {
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        let base = BuildPairHasher::new_with_keys((0, 0), (1, 1)).with_mix_seed(7);
+        let hashes = |builder: BuildPairHasher<BuildSipHasher, BuildSipHasher>| {
+            builder
+                .hashes_one(item)
+                .take(HASH_COUNT)
+                .collect::<Vec<_>>()
+        };
+
+        let shards = (0..8)
+            .map(|id| hashes(base.for_shard(id)))
+            .collect::<Vec<_>>();
+        for (i, shard1) in shards.iter().enumerate() {
+            assert_ne!(
+                shard1,
+                &hashes(BuildPairHasher::new_with_keys((0, 0), (1, 1)))
+            );
+            assert!(shards[i + 1..].iter().all(|shard2| shard1 != shard2));
+        }
+
+        assert_eq!(shards[3], hashes(base.for_shard(3)));
+        assert_eq!(base.for_shard(3).mix_seed, 7);
+
+        let other = BuildPairHasher::new_with_keys((2, 2), (3, 3)).with_mix_seed(7);
+        assert_ne!(shards[3], hashes(other.for_shard(3)));
+    }
+
+    #[test]
+    fn with_sequence() {
+        /// A strategy which only yields the first base hash.
+        #[derive(Clone)]
+        struct FirstOnly;
+
+        impl HashSequence for FirstOnly {
+            fn sequence(
+                self,
+                a: u64,
+                _b: u64,
+                _mix_seed: u64,
+            ) -> impl Iterator<Item = Hash64> + Clone {
+                std::iter::repeat(Hash64::from(a))
+            }
+        }
+
+        let item = "Hello world!";
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let first = builder.hashes_one(item).next().unwrap();
+
+        let custom = builder.with_sequence(FirstOnly);
+        assert!(custom.hashes_one(item).take(5).all(|h| h == first));
+
+        let reseeded = custom
+            .reseeded(42)
+            .hashes_one(item)
+            .take(5)
+            .collect::<Vec<_>>();
+        assert!(reseeded.windows(2).all(|w| w[0] == w[1]));
+    }
+
+    #[test]
+    fn with_mix_seed() {
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        let hashes = |builder: BuildPairHasher<BuildSipHasher, BuildSipHasher>| {
+            builder
+                .hashes_one(item)
+                .take(HASH_COUNT)
+                .collect::<Vec<_>>()
+        };
+
+        let default = hashes(BuildPairHasher::new_with_keys((0, 0), (1, 1)));
+        let zero = hashes(BuildPairHasher::new_with_keys((0, 0), (1, 1)).with_mix_seed(0));
+        let mixed1 = hashes(BuildPairHasher::new_with_keys((0, 0), (1, 1)).with_mix_seed(1));
+        let mixed2 = hashes(BuildPairHasher::new_with_keys((0, 0), (1, 1)).with_mix_seed(2));
+        let large = hashes(BuildPairHasher::new_with_keys((0, 0), (1, 1)).with_mix_seed(u64::MAX));
+
+        assert_eq!(default, zero);
+        assert_ne!(default, mixed1);
+        assert_ne!(mixed1, mixed2);
+        assert_ne!(default, large);
+    }
+
+    #[test]
+    fn default_pair_hasher_map() {
+        use std::collections::HashMap;
+
+        let mut map: HashMap<u64, String, DefaultPairHasher> = HashMap::default();
+        for key in 0..100 {
+            map.insert(key, key.to_string());
+        }
+
+        assert_eq!(map.len(), 100);
+        assert!((0..100).all(|key| map.get(&key) == Some(&key.to_string())));
+        assert_eq!(map.get(&100), None);
+
+        assert_eq!(map.remove(&42), Some("42".to_string()));
+        assert_eq!(map.get(&42), None);
+
+        let builder = DefaultPairHasher::default();
+        let expected = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        assert_eq!(builder.hash_one(42), expected.hash_one(42));
+    }
+
+    #[test]
+    fn hashes_bytes_compat() {
+        // Expected values computed with an independent SipHash-2-4 reference implementation,
+        // keyed with (0, 0) and (1, 1), and combined with the documented recurrence.
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let vectors = [
+            (
+                &b""[..],
+                (0x1e92_4b9d_7377_00d7u64, 0x616d_8985_de49_e87cu64),
+                [
+                    0x1e92_4b9d_7377_00d7,
+                    0x7fff_d523_51c0_e954,
+                    0xe16d_5ea9_300a_d1d1,
+                    0x42da_e82f_0e54_ba4e,
+                ],
+            ),
+            (
+                &b"Hello world!"[..],
+                (0xa394_d003_dfc3_b6e3, 0x253a_697d_740d_2f9a),
+                [
+                    0xa394_d003_dfc3_b6e3,
+                    0xc8cf_3981_53d0_e67e,
+                    0xee09_a2fe_c7de_1619,
+                    0x1344_0c7c_3beb_45b4,
+                ],
+            ),
+        ];
+
+        for (bytes, (a, b), expected) in vectors {
+            let hashes = builder
+                .hashes_bytes_compat(bytes)
+                .take(expected.len())
+                .map(u64::from)
+                .collect::<Vec<_>>();
+            assert_eq!(hashes, expected);
+
+            let mut hasher = builder.build_hasher();
+            hasher.write(bytes);
+            assert_eq!(hasher.finish(), a.wrapping_add(b));
+            assert_eq!(hasher.finish_pair(), (a, b));
+        }
+    }
 }
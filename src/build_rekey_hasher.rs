@@ -0,0 +1,83 @@
+use std::hash::BuildHasher;
+
+use crate::rekey_hasher::RekeyHasher;
+
+/// An instance of [`BuildHasher`] trait which builds [`RekeyHasher`] instances, so any builder,
+/// e.g. [`std::hash::RandomState`], gets the methods of [`BuildHasherExt`](crate::BuildHasherExt).
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+/// use std::hash::{BuildHasherDefault, DefaultHasher};
+///
+/// let builder = BuildRekeyHasher::new(BuildHasherDefault::<DefaultHasher>::default());
+///
+/// const HASHES_COUNT: usize = 10;
+/// let hashes = builder
+///     .hashes_one("Hello world!")
+///     .take(HASHES_COUNT)
+///     .collect::<Vec<_>>();
+/// assert_eq!(hashes.len(), HASHES_COUNT)
+///```
+#[derive(Clone, Default)]
+pub struct BuildRekeyHasher<B> {
+    builder: B,
+}
+
+impl<B> BuildRekeyHasher<B> {
+    /// Creates a new builder whose hashers wrap the hashers built by `builder`.
+    pub fn new(builder: B) -> Self {
+        Self { builder }
+    }
+}
+
+impl<B> BuildHasher for BuildRekeyHasher<B>
+where
+    B: BuildHasher,
+{
+    type Hasher = RekeyHasher<B::Hasher>;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        RekeyHasher::new(self.builder.build_hasher())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BuildHasherExt;
+    use std::hash::RandomState;
+
+    #[test]
+    fn hashes_eq() {
+        let builder = BuildRekeyHasher::new(RandomState::new());
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        let hashes1 = builder
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+        let hashes2 = builder
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        assert_eq!(hashes1, hashes2);
+        assert!(hashes1.windows(2).all(|w| w[0] != w[1]));
+    }
+
+    #[test]
+    fn default_and_clone() {
+        use std::hash::{BuildHasherDefault, DefaultHasher};
+
+        let builder = BuildRekeyHasher::<BuildHasherDefault<DefaultHasher>>::default();
+        let cloned = builder.clone();
+
+        assert!(builder
+            .hashes_one("Hello world!")
+            .take(10)
+            .eq(cloned.hashes_one("Hello world!").take(10)));
+    }
+}
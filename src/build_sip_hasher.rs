@@ -1,3 +1,4 @@
+#[cfg(feature = "rand")]
 use rand::{rngs::ThreadRng, Rng};
 use siphasher::sip::SipHasher;
 use std::hash::BuildHasher;
@@ -11,6 +12,12 @@ pub struct BuildSipHasher {
     key1: u64,
 }
 
+impl BuildSipHasher {
+    pub(crate) fn keys(&self) -> SipHasherKeys {
+        (self.key0, self.key1)
+    }
+}
+
 impl From<SipHasherKeys> for BuildSipHasher {
     fn from(keys: SipHasherKeys) -> Self {
         Self {
@@ -20,6 +27,7 @@ impl From<SipHasherKeys> for BuildSipHasher {
     }
 }
 
+#[cfg(feature = "rand")]
 impl From<ThreadRng> for BuildSipHasher {
     fn from(mut rng: ThreadRng) -> Self {
         let key0 = rng.gen();
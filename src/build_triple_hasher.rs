@@ -0,0 +1,111 @@
+use std::hash::BuildHasher;
+
+use crate::{
+    build_sip_hasher::{BuildSipHasher, SipHasherKeys},
+    triple_hasher::TripleHasher,
+};
+
+/// An instance of [`BuildHasher`] trait which builds [TripleHasher] instances, for
+/// triple hashing schemes where the `i`-th hash value is `a + i * b + i * (i - 1) / 2 * c`.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+///
+/// let builder = BuildTripleHasher::new_with_keys((0, 0), (1, 1), (2, 2));
+///
+/// const HASHE_COUNT: usize = 10;
+/// let hashes = builder
+///     .hashes_one("Hello world!")
+///     .take(HASHE_COUNT)
+///     .collect::<Vec<_>>();
+/// assert_eq!(hashes.len(), HASHE_COUNT)
+///```
+pub struct BuildTripleHasher<B1, B2, B3> {
+    builder1: B1,
+    builder2: B2,
+    builder3: B3,
+}
+
+impl<B1, B2, B3> BuildTripleHasher<B1, B2, B3> {
+    pub fn new(builder1: B1, builder2: B2, builder3: B3) -> Self {
+        Self {
+            builder1,
+            builder2,
+            builder3,
+        }
+    }
+}
+
+impl BuildTripleHasher<BuildSipHasher, BuildSipHasher, BuildSipHasher> {
+    pub fn new_with_keys(keys1: SipHasherKeys, keys2: SipHasherKeys, keys3: SipHasherKeys) -> Self {
+        let builder1 = BuildSipHasher::from(keys1);
+        let builder2 = BuildSipHasher::from(keys2);
+        let builder3 = BuildSipHasher::from(keys3);
+        Self::new(builder1, builder2, builder3)
+    }
+}
+
+impl<B1, B2, B3> BuildHasher for BuildTripleHasher<B1, B2, B3>
+where
+    B1: BuildHasher,
+    B2: BuildHasher,
+    B3: BuildHasher,
+{
+    type Hasher = TripleHasher<B1::Hasher, B2::Hasher, B3::Hasher>;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        let hasher1 = self.builder1.build_hasher();
+        let hasher2 = self.builder2.build_hasher();
+        let hasher3 = self.builder3.build_hasher();
+        TripleHasher::new(hasher1, hasher2, hasher3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BuildHasherExt;
+
+    #[test]
+    fn hashes_eq() {
+        let builder = BuildTripleHasher::new_with_keys((0, 0), (1, 1), (2, 2));
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        let hashes1 = builder
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+        let hashes2 = builder
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        assert_eq!(hashes1, hashes2);
+    }
+
+    #[test]
+    fn third_keys_independence() {
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        let hashes1 = BuildTripleHasher::new_with_keys((0, 0), (1, 1), (2, 2))
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+        let hashes2 = BuildTripleHasher::new_with_keys((0, 0), (1, 1), (3, 3))
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        // Only the first two values, `a` and `a + b`, which do not depend on the third
+        // hasher, are shared.
+        assert_eq!(hashes1[..2], hashes2[..2]);
+        assert!(hashes1[2..]
+            .iter()
+            .zip(&hashes2[2..])
+            .all(|(h1, h2)| h1 != h2));
+    }
+}
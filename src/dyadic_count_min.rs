@@ -0,0 +1,203 @@
+use crate::{BuildHasherExt, HasherExt};
+
+/// A Count-Min sketch for one level of the dyadic decomposition. The rows are indexed
+/// by the first `depth` hash values of the `(level, prefix)` pair.
+struct CountMinLevel {
+    counters: Vec<u64>,
+}
+
+/// A Count-Min sketch which answers range-frequency queries over the `0..2^log_universe` universe
+/// using the dyadic decomposition. It keeps one Count-Min sketch per level, where the level `l`
+/// counts the prefixes `value >> l`, so any range can be covered by at most two dyadic intervals
+/// per level.
+///
+/// As any Count-Min sketch, the estimates never underestimate the true counts.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+///
+/// let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+/// let mut sketch = DyadicCountMin::new(builder, 8, 256, 4);
+///
+/// sketch.add(10, 1);
+/// sketch.add(20, 2);
+/// sketch.add(200, 3);
+///
+/// assert!(sketch.range_estimate(0, 100) >= 3);
+///```
+pub struct DyadicCountMin<B> {
+    builder: B,
+    log_universe: u32,
+    width: usize,
+    depth: usize,
+    levels: Vec<CountMinLevel>,
+}
+
+impl<B> DyadicCountMin<B>
+where
+    B: BuildHasherExt,
+    B::Hasher: HasherExt,
+{
+    /// Creates a new sketch for the values in the `0..2^log_universe` range. Each level is a
+    /// Count-Min sketch with `depth` rows of `width` counters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `log_universe` is greater than 64, or if `width` or `depth` is zero.
+    pub fn new(builder: B, log_universe: u32, width: usize, depth: usize) -> Self {
+        assert!(log_universe <= 64, "the universe must fit into 64 bits");
+        assert!(width > 0, "the width must be greater than zero");
+        assert!(depth > 0, "the depth must be greater than zero");
+
+        let levels = (0..=log_universe)
+            .map(|_| CountMinLevel {
+                counters: vec![0; width * depth],
+            })
+            .collect();
+
+        Self {
+            builder,
+            log_universe,
+            width,
+            depth,
+            levels,
+        }
+    }
+
+    /// Adds `count` occurrences of the `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is outside of the universe.
+    pub fn add(&mut self, value: u64, count: u64) {
+        assert!(self.in_universe(value), "the value is outside the universe");
+
+        for level in 0..=self.log_universe {
+            let prefix = (value as u128 >> level) as u64;
+            let indices = self.counter_indices(level, prefix).collect::<Vec<_>>();
+            for index in indices {
+                let counter = &mut self.levels[level as usize].counters[index];
+                *counter = counter.saturating_add(count);
+            }
+        }
+    }
+
+    /// Returns the estimated number of occurrences of the `value`.
+    pub fn estimate(&self, value: u64) -> u64 {
+        if !self.in_universe(value) {
+            return 0;
+        }
+
+        self.level_estimate(0, value)
+    }
+
+    /// Returns the estimated number of occurrences of the values in the `low..=high` range.
+    pub fn range_estimate(&self, low: u64, high: u64) -> u64 {
+        let max = (1u128 << self.log_universe) - 1;
+        let mut lo = low as u128;
+        let mut hi = (high as u128).min(max) + 1;
+        let mut level = 0;
+        let mut sum = 0u64;
+
+        while lo < hi {
+            if lo & 1 == 1 {
+                sum = sum.saturating_add(self.level_estimate(level, lo as u64));
+                lo += 1;
+            }
+
+            if hi & 1 == 1 {
+                hi -= 1;
+                sum = sum.saturating_add(self.level_estimate(level, hi as u64));
+            }
+
+            lo >>= 1;
+            hi >>= 1;
+            level += 1;
+        }
+
+        sum
+    }
+
+    fn in_universe(&self, value: u64) -> bool {
+        (value as u128) < (1u128 << self.log_universe)
+    }
+
+    fn level_estimate(&self, level: u32, prefix: u64) -> u64 {
+        let counters = &self.levels[level as usize].counters;
+
+        self.counter_indices(level, prefix)
+            .map(|index| counters[index])
+            .min()
+            .unwrap_or_default()
+    }
+
+    fn counter_indices(&self, level: u32, prefix: u64) -> impl Iterator<Item = usize> + '_ {
+        let width = self.width;
+
+        self.builder
+            .hashes_one((level, prefix))
+            .take(self.depth)
+            .enumerate()
+            .map(move |(row, h)| row * width + (u64::from(h) % width as u64) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BuildPairHasher;
+
+    #[test]
+    fn point_estimates() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let mut sketch = DyadicCountMin::new(builder, 16, 512, 4);
+
+        for value in 0..100 {
+            sketch.add(value, value + 1);
+        }
+
+        assert!((0..100).all(|value| sketch.estimate(value) > value));
+        assert_eq!(sketch.estimate(1 << 16), 0);
+    }
+
+    #[test]
+    fn range_estimates() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        const LOG_UNIVERSE: u32 = 6;
+        let mut sketch = DyadicCountMin::new(builder, LOG_UNIVERSE, 256, 4);
+
+        let mut exact = [0u64; 1 << LOG_UNIVERSE];
+        for i in 0..500u64 {
+            let value = (i * i + 7 * i) % exact.len() as u64;
+            sketch.add(value, 1);
+            exact[value as usize] += 1;
+        }
+
+        let total = exact.iter().sum::<u64>();
+        for low in 0..exact.len() {
+            for high in low..exact.len() {
+                let expected = exact[low..=high].iter().sum::<u64>();
+                let estimate = sketch.range_estimate(low as u64, high as u64);
+
+                assert!(estimate >= expected);
+                assert!(estimate - expected <= total / 20);
+            }
+        }
+
+        assert_eq!(sketch.range_estimate(0, u64::MAX), total);
+    }
+
+    #[test]
+    fn full_universe() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let mut sketch = DyadicCountMin::new(builder, 64, 64, 2);
+
+        sketch.add(0, 1);
+        sketch.add(u64::MAX, 2);
+
+        assert_eq!(sketch.range_estimate(0, u64::MAX), 3);
+        assert!(sketch.range_estimate(u64::MAX, u64::MAX) >= 2);
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{combine_to_u128, split_u128, Hash64};
+use std::fmt::Display;
+
+/// Represents a u128 based hash value, for structures which need more than 64 bits per
+/// step to avoid collisions at scale, e.g. cuckoo filters and fingerprint tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Hash128(u128);
+
+impl Hash128 {
+    pub fn new(value: u128) -> Self {
+        Self(value)
+    }
+
+    /// Builds a 128-bit hash value from its high and low 64-bit halves.
+    pub fn from_parts(hi: Hash64, lo: Hash64) -> Self {
+        Self(combine_to_u128(hi, lo))
+    }
+
+    /// Splits the hash value into its `(hi, lo)` 64-bit halves.
+    pub fn to_parts(&self) -> (Hash64, Hash64) {
+        split_u128(self.0)
+    }
+}
+
+impl Display for Hash128 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<u128> for Hash128 {
+    fn from(value: u128) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<Hash128> for u128 {
+    fn from(value: Hash128) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parts() {
+        let hi = Hash64::from(0x0123_4567_89ab_cdef);
+        let lo = Hash64::from(42);
+        let h = Hash128::from_parts(hi, lo);
+
+        assert_eq!(u128::from(h), 0x0123_4567_89ab_cdef_0000_0000_0000_002a);
+        assert_eq!(h.to_parts(), (hi, lo));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{splitmix, Hash64};
+use std::fmt::Display;
+
+/// Represents a u32 based hash value, for memory-tight structures which only need
+/// 32 bits per step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Hash32(u32);
+
+impl Hash32 {
+    pub fn new(value: u32) -> Self {
+        Self(value)
+    }
+
+    /// Folds a 64-bit hash value into a 32-bit one. The value is first passed through the
+    /// SplitMix64 finalizer, so each of the 64 input bits affects all the output bits, and
+    /// then its two halves are xored together.
+    pub fn fold_from(h: Hash64) -> Self {
+        let mixed = splitmix::mix64(h.into());
+        Self((mixed ^ (mixed >> 32)) as u32)
+    }
+}
+
+impl Display for Hash32 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<u32> for Hash32 {
+    fn from(value: u32) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<Hash32> for u32 {
+    fn from(value: Hash32) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fold_from() {
+        let h = Hash64::from(0x0123_4567_89ab_cdef);
+        assert_eq!(Hash32::fold_from(h), Hash32::fold_from(h));
+
+        // Values which only differ in their high or low half give different folds.
+        let high = Hash32::fold_from(Hash64::from(1 << 40));
+        let low = Hash32::fold_from(Hash64::from(1 << 8));
+        assert_ne!(high, low);
+        assert_ne!(high, Hash32::fold_from(Hash64::from(0)));
+        assert_ne!(low, Hash32::fold_from(Hash64::from(0)));
+    }
+}
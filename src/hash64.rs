@@ -0,0 +1,1151 @@
+#[cfg(feature = "rand")]
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    error::Error,
+    fmt::{Binary, Display, Octal},
+    ops::{Add, Neg, Shl, Shr, Sub},
+};
+
+/// The Bitcoin base-58 alphabet, which omits the easily confused `0`, `O`, `I` and `l` characters.
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// The Mersenne prime `2^61 - 1`, commonly used for Carter-Wegman universal hashing.
+/// [`Hash64::reduce_mod_prime`] has a fast path for it.
+pub const MERSENNE_61: u64 = (1 << 61) - 1;
+
+/// Represents a u64 based hash value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Hash64(u64);
+
+impl Hash64 {
+    pub fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Returns the smaller of the two hash values.
+    pub fn min(self, other: Hash64) -> Hash64 {
+        Ord::min(self, other)
+    }
+
+    /// Returns the larger of the two hash values.
+    pub fn max(self, other: Hash64) -> Hash64 {
+        Ord::max(self, other)
+    }
+
+    /// Combines the hash value with another one, in the spirit of Boost's `hash_combine`.
+    /// The combination is order dependent, `a.combine(b)` is usually different from `b.combine(a)`.
+    pub fn combine(self, other: Hash64) -> Hash64 {
+        let seed = self.0;
+        let mixed = other
+            .0
+            .wrapping_add(0x9e37_79b9_7f4a_7c15)
+            .wrapping_add(seed << 6)
+            .wrapping_add(seed >> 2);
+
+        (seed ^ mixed).into()
+    }
+
+    /// Calculates `self + rhs`, wrapping around at the boundary of the type.
+    pub fn wrapping_add(self, rhs: Hash64) -> Hash64 {
+        self.0.wrapping_add(rhs.0).into()
+    }
+
+    /// Calculates `self - rhs`, wrapping around at the boundary of the type.
+    pub fn wrapping_sub(self, rhs: Hash64) -> Hash64 {
+        self.0.wrapping_sub(rhs.0).into()
+    }
+
+    /// Calculates `self + rhs`, returning the wrapped sum along with a boolean
+    /// indicating whether an arithmetic overflow (carry) occurred.
+    pub fn overflowing_add(self, rhs: Hash64) -> (Hash64, bool) {
+        let (value, carry) = self.0.overflowing_add(rhs.0);
+        (value.into(), carry)
+    }
+
+    /// Calculates `self * rhs`, returning the wrapped product along with a boolean
+    /// indicating whether an arithmetic overflow occurred.
+    pub fn overflowing_mul(self, rhs: Hash64) -> (Hash64, bool) {
+        let (value, overflow) = self.0.overflowing_mul(rhs.0);
+        (value.into(), overflow)
+    }
+
+    /// Returns the number of leading ones in the binary representation of the hash value.
+    pub fn leading_ones(&self) -> u32 {
+        self.0.leading_ones()
+    }
+
+    /// Returns the number of trailing ones in the binary representation of the hash value.
+    pub fn trailing_ones(&self) -> u32 {
+        self.0.trailing_ones()
+    }
+
+    /// Interleaves the bits of two 32-bit values into a 64-bit Morton code. The bits of `lo`
+    /// are placed at the even positions and the bits of `hi` at the odd positions.
+    pub fn morton_interleave(lo: u32, hi: u32) -> Hash64 {
+        (spread_bits(lo) | (spread_bits(hi) << 1)).into()
+    }
+
+    /// Splits a 64-bit Morton code into the two 32-bit values, `(lo, hi)`, it was built from.
+    /// It is the inverse of [`Hash64::morton_interleave`].
+    pub fn morton_deinterleave(&self) -> (u32, u32) {
+        (compact_bits(self.0), compact_bits(self.0 >> 1))
+    }
+
+    /// Reverses the order of the bits of the hash value.
+    pub fn reverse_bits(&self) -> Hash64 {
+        self.0.reverse_bits().into()
+    }
+
+    /// Reverses the order of the bytes of the hash value.
+    pub fn swap_bytes(&self) -> Hash64 {
+        self.0.swap_bytes().into()
+    }
+
+    /// Rotates the hash value to the left by an amount derived from another hash,
+    /// that is `other % 64` bits. It is useful for data dependent mixing.
+    pub fn rotate_by(&self, other: &Hash64) -> Hash64 {
+        self.0.rotate_left((other.0 % 64) as u32).into()
+    }
+
+    /// Returns the quotient and the remainder of the division of the hash value by the
+    /// `modulus` in one step, as needed e.g. by quotient filters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is zero.
+    pub fn div_rem(&self, modulus: u64) -> (u64, u64) {
+        (self.0 / modulus, self.0 % modulus)
+    }
+
+    /// Reduces the hash value modulo a prime, for universal hash families. The reduction
+    /// modulo [`MERSENNE_61`] uses shifts and additions instead of a division.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `prime` is zero.
+    pub fn reduce_mod_prime(&self, prime: u64) -> u64 {
+        assert_ne!(prime, 0, "the prime must be different than zero");
+
+        if prime == MERSENNE_61 {
+            let r = (self.0 & MERSENNE_61) + (self.0 >> 61);
+            if r >= MERSENNE_61 {
+                r - MERSENNE_61
+            } else {
+                r
+            }
+        } else {
+            self.0 % prime
+        }
+    }
+
+    /// Maps the hash value to a floating point number in the `[0, 1)` range,
+    /// using the 53 high bits of the hash as the mantissa.
+    pub fn as_f64_unit(&self) -> f64 {
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Maps the hash value uniformly into the `0..max` range, e.g. to use it as a jitter
+    /// in milliseconds. It uses Lemire's multiply-shift reduction, which avoids the modulo
+    /// operation and its low-bits bias; the remaining bias is at most `max / 2^64`.
+    /// For `max == 0` it returns 0.
+    pub fn scale_to(&self, max: u64) -> u64 {
+        ((self.0 as u128 * max as u128) >> 64) as u64
+    }
+
+    /// Maps the hash value into the `0..max` range like [`Hash64::scale_to`], but without any
+    /// bias: the hash values which would make some results more likely are rejected (Lemire's
+    /// method), in which case it returns `None` and the caller should try the next hash value.
+    /// At most `max` of the `2^64` hash values are rejected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is zero.
+    pub fn scale_to_unbiased(&self, max: u64) -> Option<u64> {
+        assert!(max > 0, "the range must not be empty");
+
+        let product = self.0 as u128 * max as u128;
+        let low = product as u64;
+
+        if low < max && low < max.wrapping_neg() % max {
+            return None;
+        }
+
+        Some((product >> 64) as u64)
+    }
+
+    /// Splits the hash value into four 16-bit lanes, in little-endian order
+    /// (the first lane holds the lowest bits).
+    pub fn to_u16_array(&self) -> [u16; 4] {
+        [0, 1, 2, 3].map(|i| (self.0 >> (16 * i)) as u16)
+    }
+
+    /// Builds a hash value from four 16-bit lanes, in little-endian order.
+    pub fn from_u16_array(lanes: [u16; 4]) -> Hash64 {
+        lanes
+            .iter()
+            .rev()
+            .fold(0u64, |value, &lane| (value << 16) | lane as u64)
+            .into()
+    }
+
+    /// Splits the hash value into two 32-bit lanes, in little-endian order
+    /// (the first lane holds the lowest bits).
+    pub fn to_u32_array(&self) -> [u32; 2] {
+        [self.0 as u32, (self.0 >> 32) as u32]
+    }
+
+    /// Builds a hash value from two 32-bit lanes, in little-endian order.
+    pub fn from_u32_array(lanes: [u32; 2]) -> Hash64 {
+        ((lanes[1] as u64) << 32 | lanes[0] as u64).into()
+    }
+
+    /// Picks an element of the slice by the hash value, `slice[self % slice.len()]`, e.g. to route
+    /// a request to a backend. Returns `None` if the slice is empty.
+    pub fn pick<'a, T>(&self, slice: &'a [T]) -> Option<&'a T> {
+        if slice.is_empty() {
+            return None;
+        }
+
+        slice.get((self.0 % slice.len() as u64) as usize)
+    }
+
+    /// Folds the hash value into a `bits` wide value by xoring its consecutive `bits` wide
+    /// chunks, starting from the low bits, so all the bits of the hash contribute to the result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is zero or greater than 64.
+    pub fn fold_to(&self, bits: u32) -> u64 {
+        assert!(
+            (1..=64).contains(&bits),
+            "the width must be in the 1..=64 range"
+        );
+
+        if bits == 64 {
+            return self.0;
+        }
+
+        let mask = (1 << bits) - 1;
+        let mut value = self.0;
+        let mut folded = 0;
+
+        while value != 0 {
+            folded ^= value & mask;
+            value >>= bits;
+        }
+
+        folded
+    }
+
+    /// Picks a label of the palette by the hash value, e.g. to give an item a stable color in a
+    /// visualization. It is the same as [`Hash64::pick`]. Returns `None` if the palette is empty.
+    pub fn pick_label<'a, T>(&self, palette: &'a [T]) -> Option<&'a T> {
+        self.pick(palette)
+    }
+
+    /// Returns the percentage bucket of the hash value, in the `0..100` range. It is used to
+    /// bucket users deterministically, e.g. for feature-flag rollouts.
+    pub fn percent(&self) -> u8 {
+        (self.0 % 100) as u8
+    }
+
+    /// Returns `true` if the hash value falls into a rollout of `pct` percent. Rollouts are
+    /// monotonic: a hash value in a rollout is also in all the larger rollouts.
+    pub fn in_rollout(&self, pct: u8) -> bool {
+        self.percent() < pct
+    }
+
+    /// Builds a [`StdRng`] seeded with the hash value, so secondary random number
+    /// generators can be derived deterministically from hashes. Requires the `rand` feature.
+    #[cfg(feature = "rand")]
+    pub fn seed_rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.0)
+    }
+
+    /// Encodes the hash value as a short base-58 string, using the Bitcoin alphabet.
+    ///
+    /// # Example
+    ///
+    ///```
+    /// use aabel_multihash_rs::Hash64;
+    ///
+    /// let h = Hash64::from(123456789);
+    /// let code = h.to_base58();
+    /// assert_eq!(Hash64::from_base58(&code), Ok(h));
+    ///```
+    pub fn to_base58(&self) -> String {
+        let mut value = self.0;
+        let mut digits = Vec::new();
+
+        loop {
+            digits.push(BASE58_ALPHABET[(value % 58) as usize]);
+            value /= 58;
+            if value == 0 {
+                break;
+            }
+        }
+
+        digits.iter().rev().map(|&d| d as char).collect()
+    }
+
+    /// Decodes a hash value from a base-58 string produced by [`Hash64::to_base58`].
+    pub fn from_base58(code: &str) -> Result<Hash64, ParseBase58Error> {
+        if code.is_empty() {
+            return Err(ParseBase58Error::Empty);
+        }
+
+        code.chars()
+            .try_fold(0u64, |value, c| {
+                let digit = BASE58_ALPHABET
+                    .iter()
+                    .position(|&d| d as char == c)
+                    .ok_or(ParseBase58Error::InvalidCharacter(c))?;
+
+                value
+                    .checked_mul(58)
+                    .and_then(|value| value.checked_add(digit as u64))
+                    .ok_or(ParseBase58Error::Overflow)
+            })
+            .map(Hash64::from)
+    }
+
+    /// Embeds the hash value into a 128-bit identifier together with its CRC-8 checksum.
+    /// The hash value occupies the low 64 bits and the checksum the next 8 bits, while the
+    /// remaining high bits are zero. Use [`Hash64::verify_checksum`] to validate it.
+    pub fn with_checksum(&self) -> u128 {
+        (crc8(self.0) as u128) << 64 | self.0 as u128
+    }
+
+    /// Validates a 128-bit identifier built by [`Hash64::with_checksum`] and returns the
+    /// embedded hash value. Returns `None` if the checksum does not match or if any of the
+    /// unused high bits is set.
+    pub fn verify_checksum(value: u128) -> Option<Hash64> {
+        let hash = value as u64;
+        let checksum = value >> 64;
+
+        (checksum == crc8(hash) as u128).then_some(Hash64(hash))
+    }
+}
+
+/// Computes the CRC-8 checksum (polynomial `0x07`) of the big-endian bytes of the value.
+fn crc8(value: u64) -> u8 {
+    value.to_be_bytes().iter().fold(0u8, |crc, &byte| {
+        (0..8).fold(crc ^ byte, |crc, _| {
+            if crc & 0x80 != 0 {
+                crc << 1 ^ 0x07
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
+/// Combines two hash values into a 128-bit value, e.g. to build UUID-like identifiers.
+/// The `hi` hash provides the high 64 bits and the `lo` hash the low 64 bits.
+pub fn combine_to_u128(hi: Hash64, lo: Hash64) -> u128 {
+    (hi.0 as u128) << 64 | lo.0 as u128
+}
+
+/// Splits a 128-bit value into the `(hi, lo)` hash values it was built from by [`combine_to_u128`].
+pub fn split_u128(value: u128) -> (Hash64, Hash64) {
+    (((value >> 64) as u64).into(), (value as u64).into())
+}
+
+/// Folds each hash value into an `out_bits` wide value with [`Hash64::fold_to`], e.g. to build
+/// a compact signature array from many hash values.
+///
+/// # Panics
+///
+/// Panics if `out_bits` is zero or greater than 64.
+pub fn fold_signature(hashes: &[Hash64], out_bits: u32) -> Vec<u64> {
+    hashes.iter().map(|h| h.fold_to(out_bits)).collect()
+}
+
+/// XOR-folds groups of `group` consecutive hash values into `out_bits` wide values, e.g. to
+/// build a signature shorter than the number of hash values. Each output value is the
+/// [`Hash64::fold_to`] of the XOR of its group, so the signature has
+/// `hashes.len().div_ceil(group)` values; the last group is shorter when `hashes.len()` is not
+/// a multiple of `group`. With a `group` of 1 it is [`fold_signature`].
+///
+/// # Panics
+///
+/// Panics if `group` is zero, or if `out_bits` is zero or greater than 64.
+pub fn fold_signature_grouped(hashes: &[Hash64], group: usize, out_bits: u32) -> Vec<u64> {
+    assert!(group > 0, "the group must not be empty");
+
+    hashes
+        .chunks(group)
+        .map(|chunk| {
+            let xor = chunk.iter().fold(0, |acc, h| acc ^ h.0);
+            Hash64::from(xor).fold_to(out_bits)
+        })
+        .collect()
+}
+
+/// Formats a 128-bit value as 32 lowercase hex digits grouped by dashes like a UUID,
+/// e.g. `01234567-89ab-cdef-0123-456789abcdef`.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+///
+/// let id = combine_to_u128(Hash64::from(0x0123_4567_89ab_cdef), Hash64::from(1));
+/// assert_eq!(format_uuid(id), "01234567-89ab-cdef-0000-000000000001");
+///```
+pub fn format_uuid(value: u128) -> String {
+    let hex = format!("{value:032x}");
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// Spreads the bits of a 32-bit value to the even positions of a 64-bit value.
+fn spread_bits(value: u32) -> u64 {
+    let mut x = value as u64;
+    x = (x | (x << 16)) & 0x0000_ffff_0000_ffff;
+    x = (x | (x << 8)) & 0x00ff_00ff_00ff_00ff;
+    x = (x | (x << 4)) & 0x0f0f_0f0f_0f0f_0f0f;
+    x = (x | (x << 2)) & 0x3333_3333_3333_3333;
+    (x | (x << 1)) & 0x5555_5555_5555_5555
+}
+
+/// Gathers the bits at the even positions of a 64-bit value into a 32-bit value.
+fn compact_bits(value: u64) -> u32 {
+    let mut x = value & 0x5555_5555_5555_5555;
+    x = (x | (x >> 1)) & 0x3333_3333_3333_3333;
+    x = (x | (x >> 2)) & 0x0f0f_0f0f_0f0f_0f0f;
+    x = (x | (x >> 4)) & 0x00ff_00ff_00ff_00ff;
+    x = (x | (x >> 8)) & 0x0000_ffff_0000_ffff;
+    (x | (x >> 16)) as u32
+}
+
+/// A wrapper around [`Hash64`] with a reversed ordering, so a [`BinaryHeap<MaxFirst>`](std::collections::BinaryHeap)
+/// keeps the smallest hash values at the top, e.g. to read the values of a bottom-k structure
+/// in increasing order. NB: A bounded bottom-k set itself is a max-heap of plain [`Hash64`]
+/// values, whose largest value is evicted when a smaller one arrives.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+/// use std::collections::BinaryHeap;
+///
+/// let mut heap = [5, 1, 3].map(|h| MaxFirst(Hash64::from(h))).into_iter().collect::<BinaryHeap<_>>();
+/// assert_eq!(heap.pop(), Some(MaxFirst(Hash64::from(1))));
+///```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaxFirst(pub Hash64);
+
+impl PartialOrd for MaxFirst {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MaxFirst {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+/// Adds two hash values, wrapping around at the boundary of the type.
+impl Add for Hash64 {
+    type Output = Hash64;
+
+    fn add(self, rhs: Hash64) -> Self::Output {
+        self.wrapping_add(rhs)
+    }
+}
+
+/// Subtracts two hash values, wrapping around at the boundary of the type.
+impl Sub for Hash64 {
+    type Output = Hash64;
+
+    fn sub(self, rhs: Hash64) -> Self::Output {
+        self.wrapping_sub(rhs)
+    }
+}
+
+/// Computes the two's complement of the hash value, so `h + (-h)` is zero.
+impl Neg for Hash64 {
+    type Output = Hash64;
+
+    fn neg(self) -> Self::Output {
+        self.0.wrapping_neg().into()
+    }
+}
+
+/// Shifts the hash value to the left, filling the low bits with zeros. As for `u64`,
+/// shifting by 64 or more bits overflows.
+impl Shl<u32> for Hash64 {
+    type Output = Hash64;
+
+    fn shl(self, rhs: u32) -> Self::Output {
+        (self.0 << rhs).into()
+    }
+}
+
+/// Shifts the hash value to the right, filling the high bits with zeros. As for `u64`,
+/// shifting by 64 or more bits overflows.
+impl Shr<u32> for Hash64 {
+    type Output = Hash64;
+
+    fn shr(self, rhs: u32) -> Self::Output {
+        (self.0 >> rhs).into()
+    }
+}
+
+/// The error returned when a base-58 string cannot be decoded into a [`Hash64`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseBase58Error {
+    /// The string is empty.
+    Empty,
+    /// The string contains a character outside of the base-58 alphabet.
+    InvalidCharacter(char),
+    /// The decoded value does not fit into 64 bits.
+    Overflow,
+}
+
+impl Display for ParseBase58Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "cannot decode an empty base-58 string"),
+            Self::InvalidCharacter(c) => write!(f, "invalid base-58 character {c:?}"),
+            Self::Overflow => write!(f, "base-58 value does not fit into 64 bits"),
+        }
+    }
+}
+
+impl Error for ParseBase58Error {}
+
+impl Display for Hash64 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Binary for Hash64 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Binary::fmt(&self.0, f)
+    }
+}
+
+impl Octal for Hash64 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Octal::fmt(&self.0, f)
+    }
+}
+
+impl AsRef<u64> for Hash64 {
+    fn as_ref(&self) -> &u64 {
+        &self.0
+    }
+}
+
+impl From<u64> for Hash64 {
+    fn from(value: u64) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<Hash64> for u64 {
+    fn from(value: Hash64) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildHasherExt, BuildPairHasher};
+
+    #[test]
+    fn overflowing_add() {
+        let (sum, carry) = Hash64::from(u64::MAX - 1).overflowing_add(Hash64::from(1));
+        assert_eq!(sum, Hash64::from(u64::MAX));
+        assert!(!carry);
+
+        let (sum, carry) = Hash64::from(u64::MAX).overflowing_add(Hash64::from(1));
+        assert_eq!(sum, Hash64::from(0));
+        assert!(carry);
+
+        let (sum, carry) = Hash64::from(u64::MAX).overflowing_add(Hash64::from(u64::MAX));
+        assert_eq!(sum, Hash64::from(u64::MAX - 1));
+        assert!(carry);
+    }
+
+    #[test]
+    fn overflowing_mul() {
+        let (product, overflow) =
+            Hash64::from(1 << 32).overflowing_mul(Hash64::from((1 << 32) - 1));
+        assert_eq!(product, Hash64::from(u64::MAX - ((1 << 32) - 1)));
+        assert!(!overflow);
+
+        let (product, overflow) = Hash64::from(1 << 32).overflowing_mul(Hash64::from(1 << 32));
+        assert_eq!(product, Hash64::from(0));
+        assert!(overflow);
+
+        let (product, overflow) = Hash64::from(u64::MAX).overflowing_mul(Hash64::from(2));
+        assert_eq!(product, Hash64::from(u64::MAX - 1));
+        assert!(overflow);
+    }
+
+    #[test]
+    fn base58_roundtrip() {
+        for value in [0, 1, 57, 58, 123456789, u64::MAX] {
+            let code = Hash64::from(value).to_base58();
+            assert_eq!(Hash64::from_base58(&code), Ok(Hash64::from(value)));
+        }
+
+        assert_eq!(Hash64::from(0).to_base58(), "1");
+        assert_eq!(Hash64::from(57).to_base58(), "z");
+        assert_eq!(Hash64::from(58).to_base58(), "21");
+    }
+
+    #[test]
+    fn base58_invalid() {
+        assert_eq!(
+            Hash64::from_base58("abc0"),
+            Err(ParseBase58Error::InvalidCharacter('0'))
+        );
+        assert_eq!(
+            Hash64::from_base58("Il"),
+            Err(ParseBase58Error::InvalidCharacter('I'))
+        );
+        assert_eq!(Hash64::from_base58(""), Err(ParseBase58Error::Empty));
+        assert_eq!(
+            Hash64::from_base58("zzzzzzzzzzzzz"),
+            Err(ParseBase58Error::Overflow)
+        );
+    }
+
+    #[test]
+    fn add_wraps() {
+        assert_eq!(Hash64::from(1) + Hash64::from(2), Hash64::from(3));
+        assert_eq!(Hash64::from(u64::MAX) + Hash64::from(2), Hash64::from(1));
+    }
+
+    #[test]
+    fn sub_wraps() {
+        assert_eq!(Hash64::from(3) - Hash64::from(2), Hash64::from(1));
+        assert_eq!(Hash64::from(1) - Hash64::from(2), Hash64::from(u64::MAX));
+
+        for (a, b) in [(0, 1), (5, 3), (1, u64::MAX), (u64::MAX, 1 << 63)] {
+            let (a, b) = (Hash64::from(a), Hash64::from(b));
+            assert_eq!(a - b + b, a);
+            assert_eq!(a.wrapping_sub(b), a + (-b));
+        }
+    }
+
+    #[test]
+    fn neg() {
+        for value in [0, 1, 42, 1 << 63, u64::MAX] {
+            let h = Hash64::from(value);
+            assert_eq!(h + (-h), Hash64::from(0));
+        }
+
+        assert_eq!(-Hash64::from(1), Hash64::from(u64::MAX));
+    }
+
+    #[test]
+    fn reduce_mod_mersenne() {
+        let values = [
+            0,
+            1,
+            MERSENNE_61 - 1,
+            MERSENNE_61,
+            MERSENNE_61 + 1,
+            2 * MERSENNE_61,
+            2 * MERSENNE_61 + 5,
+            7 * MERSENNE_61,
+            u64::MAX - 1,
+            u64::MAX,
+        ];
+
+        for value in values {
+            assert_eq!(
+                Hash64::from(value).reduce_mod_prime(MERSENNE_61),
+                value % MERSENNE_61
+            );
+        }
+
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        for h in builder.hashes_one("Hello world!").take(1000) {
+            assert_eq!(h.reduce_mod_prime(MERSENNE_61), u64::from(h) % MERSENNE_61);
+        }
+    }
+
+    #[test]
+    fn reduce_mod_prime() {
+        assert_eq!(Hash64::from(100).reduce_mod_prime(7), 2);
+        assert_eq!(
+            Hash64::from(u64::MAX).reduce_mod_prime(1_000_000_007),
+            u64::MAX % 1_000_000_007
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn reduce_mod_zero() {
+        Hash64::from(100).reduce_mod_prime(0);
+    }
+
+    #[test]
+    fn as_f64_unit() {
+        assert_eq!(Hash64::from(0).as_f64_unit(), 0.0);
+        assert_eq!(Hash64::from(1 << 63).as_f64_unit(), 0.5);
+
+        let max = Hash64::from(u64::MAX).as_f64_unit();
+        assert!(max < 1.0);
+        assert!(max > 0.999_999);
+    }
+
+    #[test]
+    fn scale_to_range() {
+        assert_eq!(Hash64::from(0).scale_to(1000), 0);
+        assert_eq!(Hash64::from(u64::MAX).scale_to(1000), 999);
+        assert_eq!(Hash64::from(u64::MAX).scale_to(0), 0);
+        assert_eq!(Hash64::from(1 << 63).scale_to(1000), 500);
+    }
+
+    #[test]
+    fn scale_to_unbiased() {
+        assert_eq!(Hash64::from(u64::MAX).scale_to_unbiased(1000), Some(999));
+        assert_eq!(
+            Hash64::from(1 << 63 | 1 << 12).scale_to_unbiased(1000),
+            Some(500)
+        );
+        assert_eq!(Hash64::from(1 << 63).scale_to_unbiased(1000), None);
+        assert_eq!(Hash64::from(12345).scale_to_unbiased(1), Some(0));
+
+        // With max = 3, 2^64 mod 3 = 1 value is rejected: the one whose product has a zero low word.
+        assert_eq!(Hash64::from(0).scale_to_unbiased(3), None);
+        assert_eq!(Hash64::from(1).scale_to_unbiased(3), Some(0));
+
+        // For powers of two nothing is rejected.
+        assert_eq!(Hash64::from(0).scale_to_unbiased(1 << 10), Some(0));
+    }
+
+    #[test]
+    fn scale_to_uniform() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        const MAX: u64 = 10;
+        const ITEMS: usize = 100_000;
+
+        let mut counts = [0usize; MAX as usize];
+        for item in 0..ITEMS {
+            let h = builder.hashes_one(item).next().unwrap();
+            let value = h.scale_to(MAX);
+            assert!(value < MAX);
+            counts[value as usize] += 1;
+        }
+
+        let expected = ITEMS / MAX as usize;
+        assert!(counts
+            .iter()
+            .all(|&count| count.abs_diff(expected) < expected / 10));
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn seed_rng() {
+        use rand::Rng;
+
+        let draw1: u64 = Hash64::from(42).seed_rng().gen();
+        let draw2: u64 = Hash64::from(42).seed_rng().gen();
+        let draw3: u64 = Hash64::from(43).seed_rng().gen();
+
+        assert_eq!(draw1, draw2);
+        assert_ne!(draw1, draw3);
+    }
+
+    #[test]
+    fn binary() {
+        let h = Hash64::from(5);
+        let s = format!("{:064b}", h);
+
+        assert_eq!(s.len(), 64);
+        assert!(s.ends_with("101"));
+        assert!(s[..61].chars().all(|c| c == '0'));
+        assert_eq!(format!("{:b}", h), "101");
+        assert_eq!(format!("{:#b}", h), "0b101");
+    }
+
+    #[test]
+    fn octal() {
+        let h = Hash64::from(8 * 8 * 7 + 8 + 3);
+
+        assert_eq!(format!("{:o}", h), "713");
+        assert_eq!(format!("{:#o}", h), "0o713");
+        assert_eq!(
+            format!("{:o}", Hash64::from(u64::MAX)),
+            "1777777777777777777777"
+        );
+    }
+
+    #[test]
+    fn rotate_by() {
+        let pairs = [
+            (0x0123_4567_89ab_cdef, 0),
+            (0x0123_4567_89ab_cdef, 4),
+            (0x0123_4567_89ab_cdef, 64),
+            (0x8000_0000_0000_0001, 65),
+            (u64::MAX - 1, 127),
+        ];
+
+        for (value, other) in pairs {
+            let n = (other % 64) as u32;
+            let expected = (value << n) | value.checked_shr(64 - n).unwrap_or(0);
+
+            let h = Hash64::from(value).rotate_by(&Hash64::from(other));
+            assert_eq!(h, Hash64::from(expected));
+        }
+    }
+
+    #[test]
+    fn div_rem() {
+        for value in [0, 1, 99, 100, 12345, u64::MAX] {
+            for modulus in [1, 2, 7, 100, u64::MAX] {
+                let (quotient, remainder) = Hash64::from(value).div_rem(modulus);
+
+                assert!(remainder < modulus);
+                assert_eq!(quotient * modulus + remainder, value);
+            }
+        }
+    }
+
+    #[test]
+    fn u16_array() {
+        let h = Hash64::from(0x0123_4567_89ab_cdef);
+        assert_eq!(h.to_u16_array(), [0xcdef, 0x89ab, 0x4567, 0x0123]);
+
+        for value in [0, 1, 0x0123_4567_89ab_cdef, u64::MAX] {
+            let h = Hash64::from(value);
+            assert_eq!(Hash64::from_u16_array(h.to_u16_array()), h);
+        }
+    }
+
+    #[test]
+    fn u32_array() {
+        let h = Hash64::from(0x0123_4567_89ab_cdef);
+        assert_eq!(h.to_u32_array(), [0x89ab_cdef, 0x0123_4567]);
+
+        for value in [0, 1, 0x0123_4567_89ab_cdef, u64::MAX] {
+            let h = Hash64::from(value);
+            assert_eq!(Hash64::from_u32_array(h.to_u32_array()), h);
+        }
+    }
+
+    #[test]
+    fn leading_trailing_ones() {
+        let all = Hash64::from(u64::MAX);
+        assert_eq!(all.leading_ones(), 64);
+        assert_eq!(all.trailing_ones(), 64);
+
+        let none = Hash64::from(0);
+        assert_eq!(none.leading_ones(), 0);
+        assert_eq!(none.trailing_ones(), 0);
+
+        let mixed = Hash64::from(0xf000_0000_0000_0007);
+        assert_eq!(mixed.leading_ones(), 4);
+        assert_eq!(mixed.trailing_ones(), 3);
+
+        let mixed = Hash64::from(0x7fff_ffff_ffff_fffe);
+        assert_eq!(mixed.leading_ones(), 0);
+        assert_eq!(mixed.trailing_ones(), 0);
+    }
+
+    #[test]
+    fn reverse_bits() {
+        assert_eq!(Hash64::from(1).reverse_bits(), Hash64::from(1 << 63));
+        assert_eq!(
+            Hash64::from(0x0000_0000_0000_00f1).reverse_bits(),
+            Hash64::from(0x8f00_0000_0000_0000)
+        );
+        assert_eq!(
+            Hash64::from(u64::MAX).reverse_bits(),
+            Hash64::from(u64::MAX)
+        );
+
+        let h = Hash64::from(0x0123_4567_89ab_cdef);
+        assert_eq!(h.reverse_bits().reverse_bits(), h);
+    }
+
+    #[test]
+    fn swap_bytes() {
+        assert_eq!(
+            Hash64::from(0x0123_4567_89ab_cdef).swap_bytes(),
+            Hash64::from(0xefcd_ab89_6745_2301)
+        );
+        assert_eq!(Hash64::from(0xff).swap_bytes(), Hash64::from(0xff << 56));
+    }
+
+    #[test]
+    fn max_first_k_smallest() {
+        use std::collections::BinaryHeap;
+
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        const K: usize = 10;
+
+        // Keep a bounded bottom-k set while streaming: a max-heap of at most K values whose
+        // top, the largest kept value, is evicted by any smaller incoming value.
+        let mut bottom_k = BinaryHeap::with_capacity(K + 1);
+        for item in 0..10_000u64 {
+            let h = builder.hashes_one(item).next().unwrap();
+
+            bottom_k.push(h);
+            if bottom_k.len() > K {
+                bottom_k.pop();
+            }
+            assert!(bottom_k.len() <= K);
+        }
+
+        // Read the k smallest values in increasing order.
+        let mut heap = bottom_k
+            .into_iter()
+            .map(MaxFirst)
+            .collect::<BinaryHeap<_>>();
+        let smallest = std::iter::from_fn(|| heap.pop().map(|h| h.0)).collect::<Vec<_>>();
+
+        let mut expected = (0..10_000u64)
+            .map(|item| builder.hashes_one(item).next().unwrap())
+            .collect::<Vec<_>>();
+        expected.sort();
+        expected.truncate(K);
+
+        assert_eq!(smallest, expected);
+        assert!(MaxFirst(Hash64::from(1)) > MaxFirst(Hash64::from(2)));
+    }
+
+    #[test]
+    fn morton() {
+        assert_eq!(Hash64::morton_interleave(1, 0), Hash64::from(0b01));
+        assert_eq!(Hash64::morton_interleave(0, 1), Hash64::from(0b10));
+        assert_eq!(Hash64::morton_interleave(0b11, 0b01), Hash64::from(0b0111));
+        assert_eq!(
+            Hash64::morton_interleave(u32::MAX, 0),
+            Hash64::from(0x5555_5555_5555_5555)
+        );
+        assert_eq!(
+            Hash64::morton_interleave(u32::MAX, u32::MAX),
+            Hash64::from(u64::MAX)
+        );
+
+        let pairs = [
+            (0, 0),
+            (1, 2),
+            (0xdead_beef, 0x0123_4567),
+            (u32::MAX, 0),
+            (0, u32::MAX),
+            (u32::MAX, u32::MAX),
+        ];
+        for (lo, hi) in pairs {
+            let code = Hash64::morton_interleave(lo, hi);
+            assert_eq!(code.morton_deinterleave(), (lo, hi));
+        }
+    }
+
+    #[test]
+    fn u128_roundtrip() {
+        let hi = Hash64::from(0x0123_4567_89ab_cdef);
+        let lo = Hash64::from(0xfedc_ba98_7654_3210);
+
+        let value = combine_to_u128(hi, lo);
+        assert_eq!(value, 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210);
+        assert_eq!(split_u128(value), (hi, lo));
+
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let mut hashes = builder.hashes_one("Hello world!");
+        let (hi, lo) = (hashes.next().unwrap(), hashes.next().unwrap());
+        assert_eq!(split_u128(combine_to_u128(hi, lo)), (hi, lo));
+    }
+
+    #[test]
+    fn uuid_format() {
+        assert_eq!(format_uuid(0), "00000000-0000-0000-0000-000000000000");
+        assert_eq!(
+            format_uuid(u128::MAX),
+            "ffffffff-ffff-ffff-ffff-ffffffffffff"
+        );
+        assert_eq!(
+            format_uuid(0x0123_4567_89ab_cdef_fedc_ba98_7654_3210),
+            "01234567-89ab-cdef-fedc-ba9876543210"
+        );
+    }
+
+    #[test]
+    fn min_max() {
+        let pairs = [(0, 1), (1, 0), (5, 5), (u64::MAX, 42)];
+
+        for (a, b) in pairs {
+            let (a, b) = (Hash64::from(a), Hash64::from(b));
+
+            assert_eq!(a.min(b), std::cmp::min(a, b));
+            assert_eq!(a.max(b), std::cmp::max(a, b));
+            assert!(a.min(b) <= a.max(b));
+        }
+    }
+
+    #[test]
+    fn combine() {
+        let a = Hash64::from(1);
+        let b = Hash64::from(2);
+
+        assert_eq!(a.combine(b), a.combine(b));
+        assert_ne!(a.combine(b), b.combine(a));
+        assert_ne!(a.combine(b), a);
+    }
+
+    #[test]
+    fn checksum() {
+        for value in [0, 1, 0x0123_4567_89ab_cdef, u64::MAX] {
+            let h = Hash64::from(value);
+            assert_eq!(Hash64::verify_checksum(h.with_checksum()), Some(h));
+        }
+
+        // CRC-8 check value of the ASCII string "12345678".
+        assert_eq!(crc8(u64::from_be_bytes(*b"12345678")), 0xC7);
+    }
+
+    #[test]
+    fn checksum_corrupted() {
+        let id = Hash64::from(0x0123_4567_89ab_cdef).with_checksum();
+
+        for bit in 0..72 {
+            assert_eq!(Hash64::verify_checksum(id ^ 1 << bit), None);
+        }
+
+        assert_eq!(Hash64::verify_checksum(id | 1 << 100), None);
+    }
+
+    #[test]
+    fn rollout() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let hashes = (0..1000u64)
+            .map(|user| builder.hashes_one(user).next().unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(hashes.iter().all(|h| h.percent() < 100));
+        assert!(hashes.iter().all(|h| !h.in_rollout(0) && h.in_rollout(100)));
+
+        for h in &hashes {
+            for pct in 0..100 {
+                assert!(!h.in_rollout(pct) || h.in_rollout(pct + 1));
+            }
+        }
+
+        let in_10 = hashes.iter().filter(|h| h.in_rollout(10)).count();
+        let in_20 = hashes.iter().filter(|h| h.in_rollout(20)).count();
+        assert!(in_10 < in_20);
+        assert!((50..150).contains(&in_10));
+    }
+
+    #[test]
+    fn pick() {
+        let backends = ["a", "b", "c", "d"];
+        assert_eq!(Hash64::from(6).pick(&backends), Some(&"c"));
+        assert_eq!(Hash64::from(u64::MAX).pick(&backends), Some(&"d"));
+
+        let empty: [&str; 0] = [];
+        assert_eq!(Hash64::from(6).pick(&empty), None);
+
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let mut counts = [0usize; 4];
+        for key in 0..4000u64 {
+            let h = builder.hashes_one(key).next().unwrap();
+            let backend = h.pick(&backends).unwrap();
+            counts[backends.iter().position(|b| b == backend).unwrap()] += 1;
+        }
+
+        assert!(counts.iter().all(|&count| (800..1200).contains(&count)));
+    }
+
+    #[test]
+    fn fold_to() {
+        let h = Hash64::from(0x0123_4567_89ab_cdef);
+
+        assert_eq!(h.fold_to(64), 0x0123_4567_89ab_cdef);
+        assert_eq!(h.fold_to(32), 0x0123_4567 ^ 0x89ab_cdef);
+        assert_eq!(h.fold_to(16), 0x0123 ^ 0x4567 ^ 0x89ab ^ 0xcdef);
+        assert_eq!(h.fold_to(1), u64::from(h.0.count_ones() % 2));
+        assert_eq!(
+            Hash64::from(u64::MAX).fold_to(48),
+            0xffff_ffff_ffff ^ 0xffff
+        );
+    }
+
+    #[test]
+    fn fold_signature() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let hashes = builder
+            .hashes_one("Hello world!")
+            .take(100)
+            .collect::<Vec<_>>();
+
+        for out_bits in [1, 7, 16, 33, 64] {
+            let signature = super::fold_signature(&hashes, out_bits);
+
+            assert_eq!(signature.len(), hashes.len());
+            assert_eq!(signature, super::fold_signature(&hashes, out_bits));
+            assert!(signature
+                .iter()
+                .zip(&hashes)
+                .all(|(&value, h)| value == h.fold_to(out_bits)));
+        }
+    }
+
+    #[test]
+    fn fold_signature_grouped() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let hashes = builder
+            .hashes_one("Hello world!")
+            .take(100)
+            .collect::<Vec<_>>();
+
+        for out_bits in [1, 7, 16, 33, 64] {
+            assert_eq!(
+                super::fold_signature_grouped(&hashes, 1, out_bits),
+                super::fold_signature(&hashes, out_bits)
+            );
+
+            for group in [1, 3, 7, 100, 150] {
+                let signature = super::fold_signature_grouped(&hashes, group, out_bits);
+
+                assert_eq!(signature.len(), hashes.len().div_ceil(group));
+                assert_eq!(
+                    signature,
+                    super::fold_signature_grouped(&hashes, group, out_bits)
+                );
+                assert!(signature
+                    .iter()
+                    .all(|&value| out_bits == 64 || value < 1 << out_bits));
+            }
+        }
+
+        // 100 values in groups of 7: 14 full groups and a last group of 2 values.
+        let signature = super::fold_signature_grouped(&hashes, 7, 16);
+        let last = Hash64::from(hashes[98].0 ^ hashes[99].0);
+        assert_eq!(signature[14], last.fold_to(16));
+
+        let first = hashes[..7].iter().fold(0, |acc, h| acc ^ h.fold_to(16));
+        assert_eq!(signature[0], first);
+    }
+
+    #[test]
+    fn shifts() {
+        for value in [0, 1, 0x0123_4567_89ab_cdef, u64::MAX] {
+            let h = Hash64::from(value);
+
+            for shift in [0, 1, 13, 32, 63] {
+                assert_eq!(u64::from(h << shift), value << shift);
+                assert_eq!(u64::from(h >> shift), value >> shift);
+            }
+        }
+    }
+}
@@ -0,0 +1,548 @@
+use crate::{splitmix, Hash64};
+use std::iter::FusedIterator;
+
+/// A strategy which turns the two base hashes `a` and `b` of a [`PairHasher`](crate::PairHasher)
+/// into the sequence of hash values, e.g. double hashing or enhanced double hashing.
+///
+/// The strategy is selected with [`BuildPairHasher::with_sequence`](crate::BuildPairHasher::with_sequence).
+/// It also receives the mixing seed of the builder, which a strategy may use to perturb its
+/// sequence or ignore.
+pub trait HashSequence {
+    /// Returns the **infinite** sequence of hash values for the base hashes `a` and `b`. The
+    /// sequence is [`Clone`], so it can be consumed by several consumers.
+    fn sequence(self, a: u64, b: u64, mix_seed: u64) -> impl Iterator<Item = Hash64> + Clone;
+}
+
+/// The default sequence strategy, which generates the values of a [`MultiHashIterator`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultSequence;
+
+impl HashSequence for DefaultSequence {
+    fn sequence(self, a: u64, b: u64, mix_seed: u64) -> impl Iterator<Item = Hash64> + Clone {
+        MultiHashIterator::new_with_mix_seed(a, b, mix_seed)
+    }
+}
+
+/// The **infinite** sequence of hash values generated by the default strategy of a
+/// [`PairHasher`](crate::PairHasher) from its two base hashes `a` and `b`. With `s` the
+/// mixing seed, all arithmetic wrapping:
+///
+/// ```text
+/// e[0] = a + (s >> 62),  b[0] = b | 1,  c = 4 * s
+/// e[i + 1] = e[i] + b[i]
+/// b[i + 1] = b[i] + c
+/// ```
+///
+/// so the `i`-th value is `a + (s >> 62) + i * (b | 1) + i * (i - 1) / 2 * 4 * s`. Every step `b[i]` is odd
+/// and the step increment `c` is a multiple of four, which makes the sequence a full-period
+/// walk modulo any power of two: the first `2^k` values select `2^k` distinct buckets of a
+/// table with `2^k` buckets, even when the second base hash is even or zero. The two highest
+/// bits of `s`, shifted out of `c`, are added to the first value, so distinct mixing seeds
+/// generate distinct sequences. The lowest bit of `b` does not change the sequence.
+///
+/// The iterator can be driven from base hashes computed elsewhere, e.g. stored next to the
+/// item or received from another process. The recurrence is part of the public API: for
+/// given `a`, `b` and `s` the generated values only change in a new major version.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+/// use std::hash::{BuildHasher, Hash};
+///
+/// let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1)).with_mix_seed(42);
+///
+/// let mut hasher = builder.build_hasher();
+/// "Hello world!".hash(&mut hasher);
+/// let (a, b) = hasher.finish_pair();
+///
+/// let hashes = MultiHashIterator::new_with_mix_seed(a, b, 42).take(10);
+/// assert!(hashes.eq(builder.hashes_one("Hello world!").take(10)));
+///```
+#[derive(Debug, Clone)]
+pub struct MultiHashIterator {
+    a: u64,
+    b: u64,
+    c: u64,
+}
+
+impl MultiHashIterator {
+    /// Creates the sequence for the base hashes `a` and `b`, with a zero mixing seed.
+    pub fn new(a: u64, b: u64) -> Self {
+        Self::new_with_mix_seed(a, b, 0)
+    }
+
+    /// Creates the sequence for the base hashes `a` and `b` and the mixing seed `mix_seed`.
+    pub fn new_with_mix_seed(a: u64, b: u64, mix_seed: u64) -> Self {
+        Self {
+            a: a.wrapping_add(mix_seed >> 62),
+            b: b | 1,
+            c: mix_seed << 2,
+        }
+    }
+
+    /// Skips `n` steps of the recurrence in constant time, using its closed form, all
+    /// arithmetic wrapping:
+    ///
+    /// ```text
+    /// b[n] = b + n * c
+    /// e[n] = e + n * b + n * (n - 1) / 2 * c
+    /// ```
+    fn advance(&mut self, n: usize) {
+        let n = n as u64;
+
+        // n * (n - 1) / 2, dividing the even factor first so the product can wrap.
+        let triangle = if n % 2 == 0 {
+            (n / 2).wrapping_mul(n.wrapping_sub(1))
+        } else {
+            n.wrapping_mul((n - 1) / 2)
+        };
+
+        self.a = self
+            .a
+            .wrapping_add(n.wrapping_mul(self.b))
+            .wrapping_add(triangle.wrapping_mul(self.c));
+        self.b = self.b.wrapping_add(n.wrapping_mul(self.c));
+    }
+}
+
+impl Iterator for MultiHashIterator {
+    type Item = Hash64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ret = self.a;
+        self.a = self.a.wrapping_add(self.b);
+        self.b = self.b.wrapping_add(self.c);
+
+        Some(ret.into())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.advance(n);
+        self.next()
+    }
+}
+
+impl FusedIterator for MultiHashIterator {}
+
+/// The two base hashes of an item, together with the mixing seed and the [`HashSequence`]
+/// strategy, from which the sequence of hash values can be generated any number of times.
+/// It lets a single hashing pass feed several consumers, e.g. inserting an item into two
+/// filters, without hashing the item again.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+///
+/// let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+/// let seq = builder.hash_seq("Hello world!");
+///
+/// let bits = seq.iter().take(3).collect::<Vec<_>>();
+/// let slots = seq.iter().take(5).collect::<Vec<_>>();
+/// assert_eq!(bits, slots[..3]);
+///```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashSeq<S = DefaultSequence> {
+    a: u64,
+    b: u64,
+    mix_seed: u64,
+    sequence: S,
+}
+
+impl HashSeq {
+    /// Creates the sequence of the default strategy for the base hashes `a` and `b`.
+    pub fn new(a: u64, b: u64, mix_seed: u64) -> Self {
+        Self::new_with_sequence(a, b, mix_seed, DefaultSequence)
+    }
+}
+
+impl<S> HashSeq<S> {
+    /// Creates the sequence of the `sequence` strategy for the base hashes `a` and `b`.
+    pub fn new_with_sequence(a: u64, b: u64, mix_seed: u64, sequence: S) -> Self {
+        Self {
+            a,
+            b,
+            mix_seed,
+            sequence,
+        }
+    }
+
+    /// Returns the two base hashes `(a, b)`.
+    pub fn base(&self) -> (u64, u64) {
+        (self.a, self.b)
+    }
+}
+
+impl<S: HashSequence + Clone> HashSeq<S> {
+    /// Returns a new **infinite** iterator over the sequence of hash values, which always
+    /// starts from the first value.
+    pub fn iter(&self) -> impl Iterator<Item = Hash64> + Clone {
+        self.sequence
+            .clone()
+            .sequence(self.a, self.b, self.mix_seed)
+    }
+}
+
+/// The enhanced double hashing strategy of Dillinger and Manolios. The `i`-th value is
+/// `a + i * b + (i^3 - i) / 6` (wrapping), computed incrementally: after each step `a += b`
+/// and `b += i`. The cubic term avoids the accuracy loss of plain double hashing in Bloom
+/// filters, where two items sharing both base hashes modulo the filter size collide on
+/// all their indices. The mixing seed is ignored.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+///
+/// let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1)).with_sequence(EnhancedDoubleHashing);
+/// let hashes = builder.hashes_one("Hello world!").take(10).collect::<Vec<_>>();
+/// assert_eq!(hashes.len(), 10);
+///```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EnhancedDoubleHashing;
+
+impl HashSequence for EnhancedDoubleHashing {
+    fn sequence(self, a: u64, b: u64, _mix_seed: u64) -> impl Iterator<Item = Hash64> + Clone {
+        let (mut x, mut y) = (a, b);
+
+        (0u64..).map(move |i| {
+            let ret = x;
+            x = x.wrapping_add(y);
+            y = y.wrapping_add(i + 1);
+
+            ret.into()
+        })
+    }
+}
+
+/// The double hashing strategy of Kirsch and Mitzenmacher, where the `i`-th value is
+/// `a + i * b` (wrapping). The mixing seed is ignored.
+///
+/// Kirsch and Mitzenmacher showed that a Bloom filter indexed with `(a + i * b) mod m` has
+/// asymptotically the same false positive rate as one using `k` independent hash functions.
+/// Nevertheless the scheme has some known weak spots:
+/// - Reduced modulo `m`, the sequence has a period of `m / gcd(b, m)`. When `m` is a power of
+///   two and `b` is even the period is shorter than `m`, so indices repeat earlier; for `b == 0`
+///   all the values are equal to `a`.
+/// - Two items whose base hashes `(a, b)` are equal modulo `m` collide on all their indices,
+///   which happens with probability `1 / m^2` rather than `1 / m^k`.
+///
+/// [`EnhancedDoubleHashing`] mitigates these issues.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct KirschMitzenmacher;
+
+impl HashSequence for KirschMitzenmacher {
+    fn sequence(self, a: u64, b: u64, _mix_seed: u64) -> impl Iterator<Item = Hash64> + Clone {
+        let mut x = a;
+
+        std::iter::repeat_with(move || {
+            let ret = x;
+            x = x.wrapping_add(b);
+
+            ret.into()
+        })
+    }
+}
+
+/// The triple hashing strategy, where the `i`-th value is `a + i * b + i * (i - 1) / 2 * c`
+/// (wrapping), computed incrementally with forward differences. The extra quadratic term
+/// improves the accuracy of filters using many hash values per item.
+///
+/// As a [`HashSequence`] the third base hash `c` is derived from `a` and `b` with the
+/// SplitMix64 finalizer, and the mixing seed is ignored. When a third independent hash is
+/// available, use [`TripleHashing::sequence_with`] instead. It is the sequence generated by
+/// a [`TripleHasher`](crate::TripleHasher), built by [`BuildTripleHasher`](crate::BuildTripleHasher),
+/// and by a [`MultiHasher`](crate::MultiHasher) with three hashers.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TripleHashing;
+
+impl TripleHashing {
+    /// Returns the **infinite** triple hashing sequence for the three base hashes.
+    pub fn sequence_with(a: u64, b: u64, c: u64) -> impl Iterator<Item = Hash64> + Clone {
+        let (mut x, mut y) = (a, b);
+
+        std::iter::repeat_with(move || {
+            let ret = x;
+            x = x.wrapping_add(y);
+            y = y.wrapping_add(c);
+
+            ret.into()
+        })
+    }
+}
+
+impl HashSequence for TripleHashing {
+    fn sequence(self, a: u64, b: u64, _mix_seed: u64) -> impl Iterator<Item = Hash64> + Clone {
+        let c = splitmix::mix64(a ^ b.rotate_left(32));
+        Self::sequence_with(a, b, c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildHasherExt, BuildMultiHasher, BuildPairHasher};
+
+    #[test]
+    fn default_sequence() {
+        let (a, b) = (10, 3);
+
+        let hashes = DefaultSequence.sequence(a, b, 0).take(5).map(u64::from);
+        assert!(hashes.eq([10, 13, 16, 19, 22]));
+
+        let hashes = DefaultSequence.sequence(a, b, 1).take(5).map(u64::from);
+        assert!(hashes.eq([10, 13, 20, 31, 46]));
+
+        // Even steps are made odd.
+        let hashes = DefaultSequence.sequence(a, 2, 0).take(5).map(u64::from);
+        assert!(hashes.eq([10, 13, 16, 19, 22]));
+    }
+
+    #[test]
+    fn multi_hash_iterator() {
+        let hashes = MultiHashIterator::new(10, 3).take(5).map(u64::from);
+        assert!(hashes.eq([10, 13, 16, 19, 22]));
+
+        let (a, b, mix_seed) = (0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210, 42);
+        assert!(MultiHashIterator::new_with_mix_seed(a, b, mix_seed)
+            .take(100)
+            .eq(DefaultSequence.sequence(a, b, mix_seed).take(100)));
+    }
+
+    #[test]
+    fn multi_hash_iterator_mix_seed_bits() {
+        let (a, b) = (0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210);
+        let hashes = |mix_seed| {
+            MultiHashIterator::new_with_mix_seed(a, b, mix_seed)
+                .take(4)
+                .collect::<Vec<_>>()
+        };
+
+        // Every bit of the mixing seed changes the sequence, including the two highest ones.
+        for bit in 0..64 {
+            assert_ne!(hashes(42), hashes(42 ^ (1 << bit)), "bit {bit}");
+        }
+        assert_ne!(hashes(1 << 62), hashes(2 << 62));
+        assert_ne!(hashes(1 << 62), hashes(3 << 62));
+        assert_ne!(hashes(2 << 62), hashes(3 << 62));
+    }
+
+    #[test]
+    fn multi_hash_iterator_nth() {
+        let (a, b) = (0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210);
+
+        for mix_seed in [0, 1, 42, u64::MAX] {
+            let hashes = MultiHashIterator::new_with_mix_seed(a, b, mix_seed)
+                .take(200)
+                .collect::<Vec<_>>();
+
+            for n in [0, 1, 2, 3, 10, 63, 64, 65, 198] {
+                let mut iter = MultiHashIterator::new_with_mix_seed(a, b, mix_seed);
+                assert_eq!(iter.nth(n), Some(hashes[n]));
+                assert_eq!(iter.next(), Some(hashes[n + 1]));
+            }
+
+            let stepped = MultiHashIterator::new_with_mix_seed(a, b, mix_seed)
+                .skip(5)
+                .step_by(7)
+                .take(20);
+            assert!(stepped.eq(hashes[5..].iter().step_by(7).copied().take(20)));
+
+            // Two jumps land where a single jump does.
+            let mut iter = MultiHashIterator::new_with_mix_seed(a, b, mix_seed);
+            iter.nth(1 << 40);
+            let jumped = iter.nth(12_345);
+            let mut iter = MultiHashIterator::new_with_mix_seed(a, b, mix_seed);
+            assert_eq!(jumped, iter.nth((1 << 40) + 12_346));
+        }
+
+        assert_eq!(MultiHashIterator::new(a, b).size_hint(), (usize::MAX, None));
+    }
+
+    #[test]
+    fn hash_seq() {
+        let (a, b, mix_seed) = (0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210, 42);
+
+        let seq = HashSeq::new(a, b, mix_seed);
+        assert_eq!(seq.base(), (a, b));
+        assert!(seq
+            .iter()
+            .take(10)
+            .eq(MultiHashIterator::new_with_mix_seed(a, b, mix_seed).take(10)));
+        assert!(seq.iter().take(10).eq(seq.iter().take(10)));
+
+        let seq = HashSeq::new_with_sequence(a, b, mix_seed, KirschMitzenmacher);
+        assert!(seq
+            .iter()
+            .take(10)
+            .eq(KirschMitzenmacher.sequence(a, b, mix_seed).take(10)));
+    }
+
+    #[test]
+    fn default_sequence_full_period() {
+        let mut rng = splitmix::SplitMix64::new(42);
+        let mut bases = (0..200)
+            .map(|_| (rng.next_u64(), rng.next_u64()))
+            .collect::<Vec<_>>();
+        bases.extend([(7, 0), (7, 2), (0, u64::MAX)]);
+
+        for (a, b) in bases {
+            for mix_seed in [0, 1, 42, u64::MAX] {
+                let hashes = DefaultSequence
+                    .sequence(a, b, mix_seed)
+                    .take(1 << 10)
+                    .map(u64::from)
+                    .collect::<Vec<_>>();
+
+                // The first 2^k values hit all the 2^k buckets of a table with 2^k buckets.
+                for bits in 1..=10 {
+                    let m = 1usize << bits;
+                    let mut seen = vec![false; m];
+                    for h in &hashes[..m] {
+                        seen[(h % m as u64) as usize] = true;
+                    }
+                    assert!(seen.into_iter().all(|hit| hit));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn enhanced_double_hashing() {
+        let (a, b) = (0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210);
+
+        let hashes = EnhancedDoubleHashing.sequence(a, b, 42).take(100);
+        for (i, h) in (0u64..).zip(hashes) {
+            let expected = a
+                .wrapping_add(i.wrapping_mul(b))
+                .wrapping_add((i * i * i - i) / 6);
+            assert_eq!(u64::from(h), expected);
+        }
+    }
+
+    #[test]
+    fn kirsch_mitzenmacher() {
+        // Expected values computed with an independent SipHash-2-4 reference implementation,
+        // keyed with (0, 0) and (1, 1).
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1))
+            .with_mix_seed(42)
+            .with_sequence(KirschMitzenmacher);
+        let hashes = builder.hashes_bytes(b"Hello world!").take(5).map(u64::from);
+
+        assert!(hashes.eq([
+            0xa394_d003_dfc3_b6e3,
+            0xc8cf_3981_53d0_e67d,
+            0xee09_a2fe_c7de_1617,
+            0x1344_0c7c_3beb_45b1,
+            0x387e_75f9_aff8_754b,
+        ]));
+    }
+
+    #[test]
+    fn kirsch_mitzenmacher_degenerate_steps() {
+        assert!(KirschMitzenmacher
+            .sequence(7, 0, 0)
+            .take(10)
+            .all(|h| u64::from(h) == 7));
+
+        // An even step visits only half of the indices modulo a power of two.
+        let indices = KirschMitzenmacher
+            .sequence(1, 6, 0)
+            .take(16)
+            .map(|h| u64::from(h) % 16)
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(indices.len(), 8);
+    }
+
+    #[test]
+    fn triple_hashing() {
+        let (a, b, c) = (
+            0x0123_4567_89ab_cdef,
+            0xfedc_ba98_7654_3210,
+            0x0f0f_0f0f_0f0f_0f0f,
+        );
+
+        let hashes = TripleHashing::sequence_with(a, b, c).take(100);
+        for (i, h) in (0u64..).zip(hashes) {
+            let expected = a
+                .wrapping_add(i.wrapping_mul(b))
+                .wrapping_add((i * i.saturating_sub(1) / 2).wrapping_mul(c));
+            assert_eq!(u64::from(h), expected);
+        }
+
+        let derived = splitmix::mix64(a ^ b.rotate_left(32));
+        assert!(TripleHashing
+            .sequence(a, b, 0)
+            .take(10)
+            .eq(TripleHashing::sequence_with(a, b, derived).take(10)));
+    }
+
+    #[test]
+    fn triple_hashing_with_triple_hasher() {
+        use crate::{BuildTripleHasher, HasherExt, TripleHasher};
+        use siphasher::sip::SipHasher;
+        use std::hash::{Hash, Hasher};
+
+        let item = "Hello world!";
+        let [a, b, c] = [0, 1, 2].map(|key| {
+            let mut hasher = SipHasher::new_with_keys(key, key);
+            item.hash(&mut hasher);
+            hasher.finish()
+        });
+
+        let builder = BuildTripleHasher::new_with_keys((0, 0), (1, 1), (2, 2));
+        assert!(builder
+            .hashes_one(item)
+            .take(10)
+            .eq(TripleHashing::sequence_with(a, b, c).take(10)));
+
+        // A triple hasher whose third hash is the derived one agrees with the strategy.
+        struct Fixed(u64);
+
+        impl Hasher for Fixed {
+            fn finish(&self) -> u64 {
+                self.0
+            }
+
+            fn write(&mut self, _bytes: &[u8]) {}
+        }
+
+        let derived = splitmix::mix64(a ^ b.rotate_left(32));
+        let mut hasher = TripleHasher::new(
+            SipHasher::new_with_keys(0, 0),
+            SipHasher::new_with_keys(1, 1),
+            Fixed(derived),
+        );
+        item.hash(&mut hasher);
+
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1)).with_sequence(TripleHashing);
+        assert!(hasher
+            .finish_iter()
+            .take(10)
+            .eq(builder.hashes_one(item).take(10)));
+    }
+
+    #[test]
+    fn triple_hashing_with_multi_hasher() {
+        use siphasher::sip::SipHasher;
+        use std::hash::{Hash, Hasher};
+
+        let item = "Hello world!";
+        let [a, b, c] = [0, 1, 2].map(|key| {
+            let mut hasher = SipHasher::new_with_keys(key, key);
+            item.hash(&mut hasher);
+            hasher.finish()
+        });
+
+        let builder = BuildMultiHasher::new_with_keys([(0, 0), (1, 1), (2, 2)]);
+        assert!(builder
+            .hashes_one(item)
+            .take(10)
+            .eq(TripleHashing::sequence_with(a, b, c).take(10)));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{Hash128, Hash32, Hash64, HashStreamExt};
+use std::fmt::Debug;
+
+/// Abstracts the width of the generated hash values, so a data structure can be written
+/// once and instantiated for [`Hash32`], [`Hash64`] or [`Hash128`] values.
+///
+/// Each width derives its sequence from the sequence of 64-bit values of
+/// [`HasherExt::finish_iter`](crate::HasherExt::finish_iter), see
+/// [`HasherExt::finish_iter_as`](crate::HasherExt::finish_iter_as).
+pub trait HashValue: Copy + Eq + Ord + Debug {
+    /// The number of bits of the hash value.
+    const BITS: u32;
+
+    /// Converts an infinite sequence of 64-bit hash values into a sequence of values of this width.
+    fn from_stream(stream: impl Iterator<Item = Hash64>) -> impl Iterator<Item = Self>;
+
+    /// Returns the hash value widened to 128 bits.
+    fn to_u128(&self) -> u128;
+}
+
+impl HashValue for Hash32 {
+    const BITS: u32 = 32;
+
+    fn from_stream(stream: impl Iterator<Item = Hash64>) -> impl Iterator<Item = Self> {
+        stream.map(Hash32::fold_from)
+    }
+
+    fn to_u128(&self) -> u128 {
+        u32::from(*self) as u128
+    }
+}
+
+impl HashValue for Hash64 {
+    const BITS: u32 = 64;
+
+    fn from_stream(stream: impl Iterator<Item = Hash64>) -> impl Iterator<Item = Self> {
+        stream
+    }
+
+    fn to_u128(&self) -> u128 {
+        u64::from(*self) as u128
+    }
+}
+
+impl HashValue for Hash128 {
+    const BITS: u32 = 128;
+
+    fn from_stream(stream: impl Iterator<Item = Hash64>) -> impl Iterator<Item = Self> {
+        stream
+            .group_pairs()
+            .map(|(hi, lo)| Hash128::from_parts(hi, lo))
+    }
+
+    fn to_u128(&self) -> u128 {
+        u128::from(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildHasherExt, BuildPairHasher, HasherExt};
+    use std::hash::BuildHasher;
+
+    /// A generic bucket selection, written once for all the widths.
+    fn buckets<V: HashValue>(builder: &impl BuildHasher<Hasher: HasherExt>, m: u128) -> Vec<u128> {
+        builder
+            .hashes_one_as::<V, _>("Hello world!")
+            .take(10)
+            .map(|h| h.to_u128() % m)
+            .collect()
+    }
+
+    #[test]
+    fn widths() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+
+        assert!(buckets::<Hash32>(&builder, 1000).iter().all(|&b| b < 1000));
+        assert!(buckets::<Hash64>(&builder, 1000).iter().all(|&b| b < 1000));
+        assert!(buckets::<Hash128>(&builder, 1000).iter().all(|&b| b < 1000));
+
+        assert!(builder
+            .hashes_one_as::<Hash32, _>("Hello world!")
+            .take(10)
+            .all(|h| h.to_u128() >> Hash32::BITS == 0));
+    }
+
+    #[test]
+    fn same_as_dedicated_methods() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        assert!(builder
+            .hashes_one_as::<Hash64, _>(item)
+            .take(HASH_COUNT)
+            .eq(builder.hashes_one(item).take(HASH_COUNT)));
+        assert!(builder
+            .hashes_one_as::<Hash32, _>(item)
+            .take(HASH_COUNT)
+            .eq(builder.hashes_one_u32(item).take(HASH_COUNT)));
+        assert!(builder
+            .hashes_one_as::<Hash128, _>(item)
+            .take(HASH_COUNT)
+            .eq(builder.hashes_one128(item).take(HASH_COUNT)));
+    }
+}
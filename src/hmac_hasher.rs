@@ -0,0 +1,149 @@
+use std::hash::{BuildHasher, Hasher};
+
+/// The size of the block the key is padded to, as in the HMAC construction.
+const BLOCK_SIZE: usize = 64;
+const INNER_PAD: u8 = 0x36;
+const OUTER_PAD: u8 = 0x5c;
+
+/// A [`Hasher`] which applies an HMAC-style keying over an inner [`Hasher`].
+/// The inner hasher is primed with the key xor-ed with the inner pad, the data is
+/// written into it, and the result is hashed again by a hasher primed with the key
+/// xor-ed with the outer pad.
+///
+/// It can be used as one of the components of a [`BuildPairHasher`](crate::BuildPairHasher) through [`BuildHmacHasher`].
+///
+/// NB: The construction is **not** a cryptographic HMAC unless the inner hasher is
+/// a cryptographic hash function.
+#[derive(Clone)]
+pub struct HmacHasher<H> {
+    inner: H,
+    outer: H,
+}
+
+impl<H> HmacHasher<H>
+where
+    H: Hasher + Clone,
+{
+    /// Creates a new hasher by keying the given hasher with the `key`.
+    pub fn new(hasher: H, key: &[u8]) -> Self {
+        let mut block = [0u8; BLOCK_SIZE];
+        if key.len() > BLOCK_SIZE {
+            let mut h = hasher.clone();
+            h.write(key);
+            block[..8].copy_from_slice(&h.finish().to_le_bytes());
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+
+        let mut inner = hasher.clone();
+        inner.write(&block.map(|b| b ^ INNER_PAD));
+
+        let mut outer = hasher;
+        outer.write(&block.map(|b| b ^ OUTER_PAD));
+
+        Self { inner, outer }
+    }
+}
+
+impl<H> Hasher for HmacHasher<H>
+where
+    H: Hasher + Clone,
+{
+    fn finish(&self) -> u64 {
+        let mut outer = self.outer.clone();
+        outer.write_u64(self.inner.finish());
+        outer.finish()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.inner.write(bytes);
+    }
+}
+
+/// An instance of [`BuildHasher`] trait which builds [`HmacHasher`] instances
+/// keyed with the same key.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+/// use std::hash::BuildHasher;
+/// use std::collections::hash_map::RandomState;
+///
+/// let builder1 = BuildHmacHasher::new(RandomState::new(), b"key1");
+/// let builder2 = BuildHmacHasher::new(RandomState::new(), b"key2");
+/// let builder = BuildPairHasher::new(builder1, builder2);
+///
+/// let hashes = builder.hashes_one("Hello world!").take(10).collect::<Vec<_>>();
+/// assert_eq!(hashes.len(), 10);
+///```
+pub struct BuildHmacHasher<B> {
+    builder: B,
+    key: Vec<u8>,
+}
+
+impl<B> BuildHmacHasher<B> {
+    pub fn new(builder: B, key: &[u8]) -> Self {
+        Self {
+            builder,
+            key: key.to_vec(),
+        }
+    }
+}
+
+impl<B> BuildHasher for BuildHmacHasher<B>
+where
+    B: BuildHasher,
+    B::Hasher: Clone,
+{
+    type Hasher = HmacHasher<B::Hasher>;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        HmacHasher::new(self.builder.build_hasher(), &self.key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{build_sip_hasher::BuildSipHasher, BuildHasherExt, BuildPairHasher};
+
+    fn build(key: &[u8]) -> BuildPairHasher<BuildHmacHasher<BuildSipHasher>, BuildSipHasher> {
+        let builder1 = BuildHmacHasher::new(BuildSipHasher::from((0, 0)), key);
+        let builder2 = BuildSipHasher::from((1, 1));
+        BuildPairHasher::new(builder1, builder2)
+    }
+
+    #[test]
+    fn different_keys() {
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        let hashes1 = build(b"key1")
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+        let hashes2 = build(b"key2")
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+        let hashes3 = build(b"key1")
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        assert_ne!(hashes1, hashes2);
+        assert_eq!(hashes1, hashes3);
+    }
+
+    #[test]
+    fn long_key() {
+        let key1 = [1u8; 100];
+        let key2 = [2u8; 100];
+        let item = "Hello world!";
+
+        let hash1 = build(&key1).hash_one(item);
+        let hash2 = build(&key2).hash_one(item);
+        assert_ne!(hash1, hash2);
+    }
+}
@@ -0,0 +1,123 @@
+use serde_json::Value;
+use std::hash::{Hash, Hasher};
+
+/// A wrapper which hashes a JSON value in a canonical form: the keys of the objects are
+/// visited in sorted order, so logically-equal documents hash identically regardless of
+/// the order of their keys. The lengths of arrays and objects are written as little-endian
+/// `u64` bytes, so the hash depends neither on the pointer width nor on the endianness of the
+/// target.
+pub(crate) struct CanonicalJson<'a>(pub(crate) &'a Value);
+
+impl Hash for CanonicalJson<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self.0 {
+            Value::Null => state.write_u8(0),
+            Value::Bool(b) => {
+                state.write_u8(1);
+                b.hash(state);
+            }
+            Value::Number(n) => {
+                state.write_u8(2);
+                n.to_string().hash(state);
+            }
+            Value::String(s) => {
+                state.write_u8(3);
+                s.hash(state);
+            }
+            Value::Array(values) => {
+                state.write_u8(4);
+                state.write(&(values.len() as u64).to_le_bytes());
+                values.iter().for_each(|v| CanonicalJson(v).hash(state));
+            }
+            Value::Object(map) => {
+                state.write_u8(5);
+                state.write(&(map.len() as u64).to_le_bytes());
+
+                let mut entries = map.iter().collect::<Vec<_>>();
+                entries.sort_unstable_by_key(|(k, _)| *k);
+
+                for (k, v) in entries {
+                    k.hash(state);
+                    CanonicalJson(v).hash(state);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BuildHasherExt, BuildPairHasher};
+    use serde_json::json;
+
+    #[test]
+    fn reordered_keys() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        const HASH_COUNT: usize = 10;
+
+        let value1 = json!({ "a": 1, "b": [true, null], "c": { "x": "1", "y": 2.5 } });
+        let value2 = json!({ "c": { "y": 2.5, "x": "1" }, "b": [true, null], "a": 1 });
+
+        let hashes1 = builder
+            .hashes_json(&value1)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+        let hashes2 = builder
+            .hashes_json(&value2)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        assert_eq!(hashes1, hashes2);
+    }
+
+    #[test]
+    fn lengths_are_u64() {
+        use super::CanonicalJson;
+        use std::hash::{Hash, Hasher};
+
+        /// A hasher which records the bytes written into it.
+        #[derive(Default)]
+        struct Recorder(Vec<u8>);
+
+        impl Hasher for Recorder {
+            fn finish(&self) -> u64 {
+                0
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                self.0.extend_from_slice(bytes);
+            }
+        }
+
+        for (value, tag) in [(json!([]), 4u8), (json!({}), 5)] {
+            let mut recorder = Recorder::default();
+            CanonicalJson(&value).hash(&mut recorder);
+
+            let mut expected = vec![tag];
+            expected.extend_from_slice(&0u64.to_le_bytes());
+            assert_eq!(recorder.0, expected);
+        }
+
+        // Three nulls, each hashed as its tag.
+        let mut recorder = Recorder::default();
+        CanonicalJson(&json!([null, null, null])).hash(&mut recorder);
+
+        let mut expected = vec![4u8];
+        expected.extend_from_slice(&3u64.to_le_bytes());
+        expected.extend_from_slice(&[0, 0, 0]);
+        assert_eq!(recorder.0, expected);
+    }
+
+    #[test]
+    fn different_values() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let hash = |value| builder.hashes_json(&value).next().unwrap();
+
+        assert_ne!(hash(json!({ "a": 1 })), hash(json!({ "a": 2 })));
+        assert_ne!(hash(json!({ "a": 1 })), hash(json!({ "b": 1 })));
+        assert_ne!(hash(json!([1, 2])), hash(json!([2, 1])));
+        assert_ne!(hash(json!("1")), hash(json!(1)));
+        assert_ne!(hash(json!(null)), hash(json!(false)));
+        assert_ne!(hash(json!([[1], []])), hash(json!([[], [1]])));
+    }
+}
@@ -0,0 +1,236 @@
+use std::hash::{BuildHasher, Hasher};
+
+/// A [`Hasher`] which delegates to one of two inner hashers depending on the number of bytes
+/// written: inputs of at most `threshold` bytes are hashed by the *short* hasher, longer inputs
+/// by the *long* hasher. It allows using a hasher optimized for short keys together with one
+/// optimized for bulk data.
+///
+/// The bytes are buffered until the input is known to exceed the threshold, i.e. as soon as a
+/// write would grow it past `threshold` bytes: the buffered bytes are then written into the long
+/// hasher in one call, followed by the written bytes and the rest of the input. The short hasher
+/// only hashes inputs which stayed short, from the buffer, when the hash is finished; it is
+/// cloned for that, since [`Hasher::finish`] cannot change the hasher. For hashers which process
+/// their input as a stream, e.g. [`siphasher::sip::SipHasher`], this gives the same result as
+/// writing the input directly.
+///
+/// It can be used as one of the components of a [`BuildPairHasher`](crate::BuildPairHasher) through
+/// [`BuildLengthDispatchHasher`].
+pub struct LengthDispatchHasher<S, L> {
+    short: S,
+    long: L,
+    threshold: usize,
+    buffer: Vec<u8>,
+    is_long: bool,
+}
+
+impl<S, L> LengthDispatchHasher<S, L> {
+    pub fn new(short: S, long: L, threshold: usize) -> Self {
+        Self {
+            short,
+            long,
+            threshold,
+            buffer: Vec::new(),
+            is_long: false,
+        }
+    }
+
+    /// Returns `true` if more than `threshold` bytes were written, so the long hasher is used.
+    pub fn is_long(&self) -> bool {
+        self.is_long
+    }
+}
+
+impl<S, L> Hasher for LengthDispatchHasher<S, L>
+where
+    S: Hasher + Clone,
+    L: Hasher,
+{
+    fn finish(&self) -> u64 {
+        if self.is_long {
+            return self.long.finish();
+        }
+
+        let mut short = self.short.clone();
+        if !self.buffer.is_empty() {
+            short.write(&self.buffer);
+        }
+        short.finish()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        if self.is_long {
+            self.long.write(bytes);
+            return;
+        }
+
+        if self.buffer.len() + bytes.len() > self.threshold {
+            if !self.buffer.is_empty() {
+                self.long.write(&self.buffer);
+            }
+            self.long.write(bytes);
+            self.buffer = Vec::new();
+            self.is_long = true;
+        } else {
+            self.buffer.extend_from_slice(bytes);
+        }
+    }
+}
+
+/// An instance of [`BuildHasher`] trait which builds [`LengthDispatchHasher`] instances
+/// with the same threshold.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+/// use std::collections::hash_map::RandomState;
+///
+/// let builder1 = BuildLengthDispatchHasher::new(RandomState::new(), RandomState::new(), 16);
+/// let builder2 = BuildLengthDispatchHasher::new(RandomState::new(), RandomState::new(), 16);
+/// let builder = BuildPairHasher::new(builder1, builder2);
+///
+/// let hashes = builder.hashes_one("Hello world!").take(10).collect::<Vec<_>>();
+/// assert_eq!(hashes.len(), 10);
+///```
+pub struct BuildLengthDispatchHasher<BS, BL> {
+    short: BS,
+    long: BL,
+    threshold: usize,
+}
+
+impl<BS, BL> BuildLengthDispatchHasher<BS, BL> {
+    pub fn new(short: BS, long: BL, threshold: usize) -> Self {
+        Self {
+            short,
+            long,
+            threshold,
+        }
+    }
+}
+
+impl<BS, BL> BuildHasher for BuildLengthDispatchHasher<BS, BL>
+where
+    BS: BuildHasher,
+    BS::Hasher: Clone,
+    BL: BuildHasher,
+{
+    type Hasher = LengthDispatchHasher<BS::Hasher, BL::Hasher>;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        LengthDispatchHasher::new(
+            self.short.build_hasher(),
+            self.long.build_hasher(),
+            self.threshold,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{build_sip_hasher::BuildSipHasher, BuildHasherExt, BuildPairHasher};
+
+    const THRESHOLD: usize = 16;
+
+    fn build() -> BuildLengthDispatchHasher<BuildSipHasher, BuildSipHasher> {
+        BuildLengthDispatchHasher::new(
+            BuildSipHasher::from((0, 0)),
+            BuildSipHasher::from((1, 1)),
+            THRESHOLD,
+        )
+    }
+
+    fn hash_bytes<B: BuildHasher>(builder: &B, chunks: &[&[u8]]) -> u64 {
+        let mut hasher = builder.build_hasher();
+        for chunk in chunks {
+            hasher.write(chunk);
+        }
+        hasher.finish()
+    }
+
+    #[test]
+    fn dispatch_by_length() {
+        let builder = build();
+        let short = BuildSipHasher::from((0, 0));
+        let long = BuildSipHasher::from((1, 1));
+
+        let short_input: &[&[u8]] = &[b"Hello", b" world!"];
+        let mut hasher = builder.build_hasher();
+        short_input.iter().for_each(|chunk| hasher.write(chunk));
+        assert!(!hasher.is_long());
+        assert_eq!(hasher.finish(), hash_bytes(&short, short_input));
+
+        let long_input: &[&[u8]] = &[b"Hello", b" world!", b" Hello", b" again!"];
+        let mut hasher = builder.build_hasher();
+        long_input.iter().for_each(|chunk| hasher.write(chunk));
+        assert!(hasher.is_long());
+        assert_eq!(hasher.finish(), hash_bytes(&long, long_input));
+
+        let at_threshold: &[&[u8]] = &[&[7; THRESHOLD]];
+        assert_eq!(
+            hash_bytes(&builder, at_threshold),
+            hash_bytes(&short, at_threshold)
+        );
+    }
+
+    /// Counts the bytes written into it.
+    #[derive(Clone, Default)]
+    struct CountingHasher {
+        written: usize,
+    }
+
+    impl Hasher for CountingHasher {
+        fn finish(&self) -> u64 {
+            self.written as u64
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.written += bytes.len();
+        }
+    }
+
+    #[test]
+    fn short_hasher_only_hashes_short_inputs() {
+        let mut hasher =
+            LengthDispatchHasher::new(CountingHasher::default(), CountingHasher::default(), 4);
+        hasher.write(b"abc");
+        assert_eq!(hasher.finish(), 3);
+        assert_eq!(hasher.short.written, 0);
+        assert_eq!(hasher.long.written, 0);
+
+        // A single write past the threshold goes to the long hasher only.
+        let mut hasher =
+            LengthDispatchHasher::new(CountingHasher::default(), CountingHasher::default(), 4);
+        hasher.write(&[0; 1024]);
+        assert!(hasher.is_long());
+        assert_eq!(hasher.finish(), 1024);
+        assert_eq!(hasher.short.written, 0);
+
+        let mut hasher =
+            LengthDispatchHasher::new(CountingHasher::default(), CountingHasher::default(), 4);
+        hasher.write(b"abc");
+        hasher.write(b"de");
+        assert!(hasher.is_long());
+        assert_eq!(hasher.finish(), 5);
+        assert_eq!(hasher.short.written, 0);
+    }
+
+    #[test]
+    fn deterministic_streams() {
+        let builder = BuildPairHasher::new(build(), build());
+        const HASH_COUNT: usize = 10;
+
+        for item in ["short", "a much longer input than the threshold"] {
+            let hashes1 = builder
+                .hashes_one(item)
+                .take(HASH_COUNT)
+                .collect::<Vec<_>>();
+            let hashes2 = builder
+                .hashes_one(item)
+                .take(HASH_COUNT)
+                .collect::<Vec<_>>();
+
+            assert_eq!(hashes1, hashes2);
+        }
+    }
+}
@@ -1,6 +1,7 @@
-//! A crate which defines two extensions:
+//! A crate which defines three extensions:
 //! - [`HasherExt`] trait which extends the [`Hasher`] trait.
 //! - [`BuildHasherExt`] trait which extends the [`BuildHasher`] trait.
+//! - [`HashStreamExt`] trait which extends the iterators over the generated hash values.
 //!
 //! The purpose of these extensions is to allow the callers to compute
 //! sequences of hash values for any item that is hashable. This functionality
@@ -8,7 +9,7 @@
 //!
 //! The [`PairHasher`] implements the [`HasherExt`] trait. It a combinator of two [`Hasher`] instances which are used in order to generate the sequence of hash values.
 //!
-//! The [`PairHasherBuilder`] implements the [`BuildHasherExt`] trait. It provides a convenient way to build [`PairHasher`] instances. It also prives convenient *new* functions
+//! The [`BuildPairHasher`] implements the [`BuildHasherExt`] trait. It provides a convenient way to build [`PairHasher`] instances. It also provides convenient *new* functions
 //! which allow the user to create [`PairHasher`] instances by combining two [`siphasher::sip::SipHasher`] instances.
 //!
 //! # Example
@@ -32,68 +33,185 @@
 //! assert_eq!(hashes.len(), HASHE_COUNT)
 //!```
 use std::{
-    fmt::Display,
+    collections::{HashSet, VecDeque},
     hash::{BuildHasher, Hash, Hasher},
 };
 
+mod bloom_filter;
+mod build_multi_hasher;
 mod build_pair_hasher;
+mod build_rekey_hasher;
 mod build_sip_hasher;
+mod build_triple_hasher;
+mod dyadic_count_min;
+mod hash128;
+mod hash32;
+mod hash64;
+mod hash_sequence;
+mod hash_value;
+mod hmac_hasher;
+#[cfg(feature = "serde_json")]
+mod json;
+mod length_dispatch_hasher;
+mod masked_hasher;
+mod morris;
+mod multi_hasher;
+mod multiset_hash;
 mod pair_hasher;
+mod perfect_hash;
+mod quotient_filter;
+mod rekey_hasher;
+mod rendezvous;
+mod shuffle;
+mod splitmix;
+#[cfg(any(test, feature = "testutil"))]
+pub mod testutil;
+mod tie_break;
+mod triple_hasher;
 
+pub use bloom_filter::*;
+pub use build_multi_hasher::*;
 pub use build_pair_hasher::*;
-// pub use pair_hasher::*;
+pub use build_rekey_hasher::*;
+pub use build_sip_hasher::SipHasherKeys;
+pub use build_triple_hasher::*;
+pub use dyadic_count_min::*;
+pub use hash128::*;
+pub use hash32::*;
+pub use hash64::*;
+pub use hash_sequence::*;
+pub use hash_value::*;
+pub use hmac_hasher::*;
+pub use length_dispatch_hasher::*;
+pub use masked_hasher::*;
+pub use morris::*;
+pub use multi_hasher::*;
+pub use multiset_hash::*;
+pub use pair_hasher::*;
+pub use perfect_hash::*;
+pub use quotient_filter::*;
+pub use rekey_hasher::*;
+pub use rendezvous::*;
+pub use shuffle::*;
+pub use tie_break::*;
+pub use triple_hasher::*;
 
-/// Represents a u64 based hash value.
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Hash64(u64);
+/// Extends the [`Hasher`] trait by providing a mechanism to
+/// get a sequence of hash values when the hashing operation is finalized.
+pub trait HasherExt: Hasher {
+    /// Returns an **infinite** sequence of hash values for the values written so far.
+    /// NB: Before you call collect on this iterator, please make sure you have reduce the
+    /// collection to a finite sequece by calling take, for example.
+    ///
+    /// Its behavior it is different than the [`Hasher::finish`]s one. The method consumes
+    /// the hasher instance, so to generate new hashes you need to rebuild the hasher instance.
+    fn finish_iter(self) -> impl Iterator<Item = Hash64> + Clone;
 
-impl Hash64 {
-    pub fn new(value: u64) -> Self {
-        Self(value)
+    /// Returns the **infinite** sequence of hash values for the values written so far, like
+    /// [`HasherExt::finish_iter`], but without consuming the hasher: the sequence is generated
+    /// from a snapshot of its state. The returned iterator does not borrow the hasher, so more
+    /// data can be written into it afterwards, e.g. for incremental multi-stage hashing.
+    fn finish_iter_ref(&self) -> impl Iterator<Item = Hash64> + Clone + use<Self>
+    where
+        Self: Clone,
+    {
+        self.clone().finish_iter()
     }
-}
 
-impl Display for Hash64 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+    /// Returns the two base hashes `(a, b)` of the sequence, so they can be plugged into custom
+    /// sequence schemes, e.g. a fingerprint and a bucket derivation. For the [`PairHasher`] they
+    /// are the results of its two hashers.
+    ///
+    /// The default implementation returns the first value of [`HasherExt::finish_iter`] and the
+    /// (wrapping) difference between the second and the first value.
+    fn finish_pair(self) -> (u64, u64)
+    where
+        Self: Sized,
+    {
+        let [e0, e1] = self.finish_array();
+        (e0.into(), u64::from(e1.wrapping_sub(e0)))
     }
-}
 
-impl AsRef<u64> for Hash64 {
-    fn as_ref(&self) -> &u64 {
-        &self.0
+    /// Returns the first `K` hash values of [`HasherExt::finish_iter`] in a stack array, for
+    /// structures which need a number of hash values known at compile time.
+    fn finish_array<const K: usize>(self) -> [Hash64; K]
+    where
+        Self: Sized,
+    {
+        let mut hashes = self.finish_iter();
+        std::array::from_fn(|_| hashes.next().expect("the hash sequence is infinite"))
     }
-}
 
-impl From<u64> for Hash64 {
-    fn from(value: u64) -> Self {
-        Self::new(value)
+    /// Returns an **infinite** sequence of 128-bit hash values for the values written so far.
+    /// Each value combines two consecutive values of [`HasherExt::finish_iter`], the first one
+    /// giving the high bits and the second one the low bits.
+    ///
+    /// For the default sequence of [`BuildPairHasher`], each pair of consecutive values is a
+    /// bijection of the first base hash and of the second base hash with its lowest bit set,
+    /// see [`MultiHashIterator`]. So the 128-bit values of two items collide only if their first
+    /// base hashes collide and their second base hashes collide, ignoring the lowest bit.
+    fn finish_iter128(self) -> impl Iterator<Item = Hash128>
+    where
+        Self: Sized,
+    {
+        self.finish_iter_as()
     }
-}
 
-impl From<Hash64> for u64 {
-    fn from(value: Hash64) -> Self {
-        value.0
+    /// Returns an **infinite** sequence of 32-bit hash values for the values written so far.
+    /// Each value of [`HasherExt::finish_iter`] is folded into 32 bits with [`Hash32::fold_from`],
+    /// so all the 64 bits of each step contribute to the 32-bit value.
+    fn finish_iter_u32(self) -> impl Iterator<Item = Hash32>
+    where
+        Self: Sized,
+    {
+        self.finish_iter_as()
     }
-}
 
-/// Extends the [`Hasher`] trait by providing a mechanism to
-/// get a sequence of hash values when the hashing operation is finalized.
-pub trait HasherExt: Hasher {
-    /// Returns an **infinite** sequence of hash values for the values written so far.
-    /// NB: Before you call collect on this iterator, please make sure you have reduce the
-    /// collection to a finite sequece by calling take, for example.
-    ///
-    /// Its behavior it is different than the [`Hasher::finish`]s one. The method consumes
-    /// the hasher instance, so to generate new hashes you need to rebuild the hasher instance.
-    fn finish_iter(self) -> impl Iterator<Item = Hash64>;
+    /// Returns an **infinite** sequence of hash values of any width implementing [`HashValue`],
+    /// so data structures can be written once for all the widths. [`HasherExt::finish_iter`]
+    /// keeps returning [`Hash64`] values, which is the same as `finish_iter_as::<Hash64>`.
+    fn finish_iter_as<V: HashValue>(self) -> impl Iterator<Item = V>
+    where
+        Self: Sized,
+    {
+        V::from_stream(self.finish_iter())
+    }
 }
 
 /// Extends the [`BuildHasher`] trait by allowing to compute the sequence of hash values
 /// for one given hashable value.
 pub trait BuildHasherExt: BuildHasher {
     /// Generates the sequece of hash values for a given item.
-    fn hashes_one<T: Hash>(&self, item: T) -> impl Iterator<Item = Hash64>
+    ///
+    /// A single hasher is built and the item is hashed only once, however many values are
+    /// taken from the sequence, e.g. the `k` bit positions of a Bloom filter.
+    ///
+    /// # Example
+    ///
+    ///```
+    /// use aabel_multihash_rs::*;
+    ///
+    /// const M_BITS: u64 = 1024;
+    /// const K: usize = 7;
+    ///
+    /// let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+    /// let mut bits = vec![false; M_BITS as usize];
+    ///
+    /// // Insert an item.
+    /// for h in builder.hashes_one("Hello world!").take(K) {
+    ///     bits[(u64::from(h) % M_BITS) as usize] = true;
+    /// }
+    ///
+    /// // Check the membership of an item, stopping at the first unset bit.
+    /// let contains = |item| {
+    ///     builder
+    ///         .hashes_one(item)
+    ///         .take(K)
+    ///         .all(|h| bits[(u64::from(h) % M_BITS) as usize])
+    /// };
+    /// assert!(contains("Hello world!"));
+    ///```
+    fn hashes_one<T: Hash>(&self, item: T) -> impl Iterator<Item = Hash64> + Clone
     where
         Self::Hasher: HasherExt,
     {
@@ -102,6 +220,373 @@ pub trait BuildHasherExt: BuildHasher {
         item.hash(&mut hasher);
         hasher.finish_iter()
     }
+
+    /// Generates an **infinite** sequence of indices in the `0..m` range for a given item,
+    /// e.g. the bucket indices of a filter. The hash values are reduced without modulo bias
+    /// with [`Hash64::scale_to_unbiased`], skipping the rare hash values which are rejected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `m` is zero.
+    fn indices_one<T: Hash>(&self, item: T, m: usize) -> impl Iterator<Item = usize>
+    where
+        Self::Hasher: HasherExt,
+    {
+        assert!(m > 0, "the range must not be empty");
+
+        self.hashes_one(item)
+            .filter_map(move |h| h.scale_to_unbiased(m as u64))
+            .map(|index| index as usize)
+    }
+
+    /// Generates `k` **distinct** indices in the `0..m` range for a given item, in the order of
+    /// its sequence of hash values. The indices are drawn as in [`BuildHasherExt::indices_one`];
+    /// when an index collides with one of the previous indices, the next free index is found by
+    /// linear probing, wrapping around at `m`. Unlike [`BuildHasherExt::k_index_set`], which keeps
+    /// drawing hash values, it consumes exactly one index of the sequence per returned index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is greater than `m`, since there are not enough distinct indices.
+    fn indices_one_distinct<T: Hash>(&self, item: T, m: usize, k: usize) -> Vec<usize>
+    where
+        Self::Hasher: HasherExt,
+    {
+        assert!(k <= m, "there must be at least k indices in the range");
+
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let mut seen = HashSet::with_capacity(k);
+
+        self.indices_one(item, m)
+            .take(k)
+            .map(|mut index| {
+                while !seen.insert(index) {
+                    index = (index + 1) % m;
+                }
+                index
+            })
+            .collect()
+    }
+
+    /// Generates `k` indices for a given item, for partitioned Bloom filters: the `0..m` range is
+    /// split into `k` partitions and the `i`-th index falls into its own partition
+    /// `i * m / k .. (i + 1) * m / k`. The hash values are reduced without modulo bias, as in
+    /// [`BuildHasherExt::indices_one`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `m` is smaller than `k`, since some partitions would be empty.
+    fn partitioned_indices<T: Hash>(
+        &self,
+        item: T,
+        m: usize,
+        k: usize,
+    ) -> impl Iterator<Item = usize>
+    where
+        Self::Hasher: HasherExt,
+    {
+        assert!(m >= k, "each partition must have at least one index");
+
+        let bound = move |i: usize| (i as u128 * m as u128 / k as u128) as usize;
+        let mut hashes = self.hashes_one(item);
+
+        (0..k).map(move |i| {
+            let (start, end) = (bound(i), bound(i + 1));
+            let offset = hashes
+                .by_ref()
+                .find_map(|h| h.scale_to_unbiased((end - start) as u64))
+                .expect("the hash sequence is infinite");
+
+            start + offset as usize
+        })
+    }
+
+    /// Writes the first `out.len()` hash values of a given item into a caller provided buffer,
+    /// so hot loops can reuse the same buffer instead of collecting into a new vector per item.
+    fn hashes_one_into<T: Hash>(&self, item: T, out: &mut [Hash64])
+    where
+        Self::Hasher: HasherExt,
+    {
+        for (slot, h) in out.iter_mut().zip(self.hashes_one(item)) {
+            *slot = h;
+        }
+    }
+
+    /// Hashes a batch of items and returns their first `k` hash values as a flat vector of rows,
+    /// so the hash values of the `i`-th item are `hashes[i * k..(i + 1) * k]`, e.g. for bulk
+    /// loading a filter. The vector is allocated once for the whole batch.
+    fn hashes_many<I>(&self, items: I, k: usize) -> Vec<Hash64>
+    where
+        Self::Hasher: HasherExt,
+        I: IntoIterator,
+        I::Item: Hash,
+    {
+        let items = items.into_iter();
+        let mut hashes = Vec::with_capacity(items.size_hint().0.saturating_mul(k));
+
+        for item in items {
+            hashes.extend(self.hashes_one(item).take(k));
+        }
+
+        hashes
+    }
+
+    /// Generates the sequence of hash values of any width for a given item, see [`HasherExt::finish_iter_as`].
+    fn hashes_one_as<V: HashValue, T: Hash>(&self, item: T) -> impl Iterator<Item = V>
+    where
+        Self::Hasher: HasherExt,
+    {
+        let mut hasher = self.build_hasher();
+
+        item.hash(&mut hasher);
+        hasher.finish_iter_as()
+    }
+
+    /// Generates the sequence of 128-bit hash values for a given item, see [`HasherExt::finish_iter128`].
+    fn hashes_one128<T: Hash>(&self, item: T) -> impl Iterator<Item = Hash128>
+    where
+        Self::Hasher: HasherExt,
+    {
+        let mut hasher = self.build_hasher();
+
+        item.hash(&mut hasher);
+        hasher.finish_iter128()
+    }
+
+    /// Generates the sequence of 32-bit hash values for a given item, see [`HasherExt::finish_iter_u32`].
+    fn hashes_one_u32<T: Hash>(&self, item: T) -> impl Iterator<Item = Hash32>
+    where
+        Self::Hasher: HasherExt,
+    {
+        let mut hasher = self.build_hasher();
+
+        item.hash(&mut hasher);
+        hasher.finish_iter_u32()
+    }
+
+    /// Generates the sequence of hash values for a slice of bytes. The bytes are written
+    /// directly into the hasher, without the length prefix added by the [`Hash`] implementation
+    /// of slices.
+    ///
+    /// The output depends only on the bytes, so it is the same for a `Vec<u8>`, a `&[u8]`,
+    /// a `Box<[u8]>` or any other container holding the same bytes. Hashing these containers
+    /// with [`BuildHasherExt::hashes_one`] also gives identical sequences, as they share the
+    /// [`Hash`] implementation of slices, but the sequences differ from the ones of this method.
+    fn hashes_bytes(&self, bytes: &[u8]) -> impl Iterator<Item = Hash64>
+    where
+        Self::Hasher: HasherExt,
+    {
+        let mut hasher = self.build_hasher();
+
+        hasher.write(bytes);
+        hasher.finish_iter()
+    }
+
+    /// Generates the sequence of hash values for the bytes produced by an iterator, without
+    /// collecting them. The bytes are written into the hasher in buffered chunks.
+    ///
+    /// For hashers which process their input as a stream, e.g. [`siphasher::sip::SipHasher`],
+    /// the result is the same as calling [`BuildHasherExt::hashes_bytes`] with the collected bytes.
+    fn hashes_from_byte_iter<I>(&self, bytes: I) -> impl Iterator<Item = Hash64>
+    where
+        Self::Hasher: HasherExt,
+        I: IntoIterator<Item = u8>,
+    {
+        const CHUNK_SIZE: usize = 1024;
+
+        let mut hasher = self.build_hasher();
+        let mut chunk = [0u8; CHUNK_SIZE];
+        let mut len = 0;
+
+        for byte in bytes {
+            chunk[len] = byte;
+            len += 1;
+
+            if len == CHUNK_SIZE {
+                hasher.write(&chunk);
+                len = 0;
+            }
+        }
+
+        hasher.write(&chunk[..len]);
+        hasher.finish_iter()
+    }
+
+    /// Generates the sequence of hash values for a given item, mapping each value
+    /// into a domain specific type, e.g. a bucket index.
+    fn map_into<T, U, F>(&self, item: T, f: F) -> impl Iterator<Item = U>
+    where
+        Self::Hasher: HasherExt,
+        T: Hash,
+        F: FnMut(Hash64) -> U,
+    {
+        self.hashes_one(item).map(f)
+    }
+
+    /// Generates the sequence of hash values for a JSON document. The value is walked in a
+    /// canonical order, with the keys of the objects sorted, so logically-equal documents
+    /// produce the same sequence regardless of the order of their keys.
+    #[cfg(feature = "serde_json")]
+    fn hashes_json(&self, value: &serde_json::Value) -> impl Iterator<Item = Hash64>
+    where
+        Self::Hasher: HasherExt,
+    {
+        self.hashes_one(json::CanonicalJson(value))
+    }
+
+    /// Generates the sequence of hash values for a floating point number. The value is
+    /// canonicalized before hashing: `-0.0` is hashed as `+0.0` and all the `NaN` values
+    /// are hashed as the same bit pattern, so values which compare equal hash identically.
+    fn hashes_f64(&self, x: f64) -> impl Iterator<Item = Hash64>
+    where
+        Self::Hasher: HasherExt,
+    {
+        let bits = if x == 0.0 {
+            0.0f64.to_bits()
+        } else if x.is_nan() {
+            f64::NAN.to_bits()
+        } else {
+            x.to_bits()
+        };
+
+        self.hashes_one(bits)
+    }
+
+    /// Returns a set of `k` distinct indices in the `0..modulus` range for a given item.
+    /// The indices are the ones of [`BuildHasherExt::indices_one_distinct`], reduced without
+    /// modulo bias, and colliding indices are resolved by linear probing, so a fixed number of
+    /// hash values is drawn even for degenerate sequences, e.g. a constant one. The number of
+    /// indices is capped at `modulus`, so the set has `min(k, modulus)` elements.
+    fn k_index_set<T: Hash>(&self, item: T, k: usize, modulus: usize) -> HashSet<usize>
+    where
+        Self::Hasher: HasherExt,
+    {
+        self.indices_one_distinct(item, modulus, k.min(modulus))
+            .into_iter()
+            .collect()
+    }
+
+    /// Chooses `k` distinct indices out of `0..n`, deterministically for a given `seed`.
+    /// It performs a partial Fisher-Yates shuffle driven by the sequence of hash values of
+    /// the seed. If `k` is greater than `n`, only `n` indices are returned.
+    fn sample_indices<T: Hash>(&self, seed: T, n: usize, k: usize) -> Vec<usize>
+    where
+        Self::Hasher: HasherExt,
+    {
+        let k = k.min(n);
+        let mut indices = (0..n).collect::<Vec<_>>();
+
+        for (i, h) in (0..k).zip(self.hashes_one(seed)) {
+            let j = i + (u64::from(h) % (n - i) as u64) as usize;
+            indices.swap(i, j);
+        }
+
+        indices.truncate(k);
+        indices
+    }
+
+    /// Returns a permutation of `0..n`, deterministically for a given `seed`. It is the
+    /// full Fisher-Yates shuffle of [`BuildHasherExt::sample_indices`], with `k = n`.
+    fn permutation<T: Hash>(&self, seed: T, n: usize) -> Vec<usize>
+    where
+        Self::Hasher: HasherExt,
+    {
+        self.sample_indices(seed, n, n)
+    }
+
+    /// Samples a geometric level for the item, e.g. for the level of a skip-list node. The level
+    /// is the number of consecutive values of the sequence, mapped to `[0, 1)` with
+    /// [`Hash64::as_f64_unit`], which fall below `p`. So the level is at least `l` with
+    /// probability `p^l`.
+    ///
+    /// NB: The consecutive values of a double hashing sequence are not independent (without a
+    /// mixing seed, any three of them are in arithmetic progression), so the values are
+    /// decorrelated with [`HashStreamExt::finalized`] before being compared with `p`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not in the `[0, 1)` range.
+    fn geometric_level<T: Hash>(&self, item: T, p: f64) -> u32
+    where
+        Self::Hasher: HasherExt,
+    {
+        assert!((0.0..1.0).contains(&p), "the probability must be in [0, 1)");
+
+        self.hashes_one(item)
+            .finalized()
+            .take_while(|h| h.as_f64_unit() < p)
+            .count() as u32
+    }
+
+    /// Returns two candidate buckets in the `0..m` range for the item, for load balancing with
+    /// the *power of two choices*. The first bucket comes from the first hash value, the second
+    /// one from the second hash value, skipping the first bucket, so the two buckets are
+    /// distinct whenever `m` is at least 2. For `m == 1` both buckets are 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `m` is zero.
+    fn two_choice_buckets<T: Hash>(&self, item: T, m: usize) -> (usize, usize)
+    where
+        Self::Hasher: HasherExt,
+    {
+        assert!(m > 0, "there must be at least one bucket");
+
+        if m == 1 {
+            return (0, 0);
+        }
+
+        let mut hashes = self.hashes_one(item);
+        let h1 = hashes.next().expect("the hash sequence is infinite");
+        let h2 = hashes.next().expect("the hash sequence is infinite");
+
+        let bucket1 = (u64::from(h1) % m as u64) as usize;
+        let bucket2 = (u64::from(h2) % (m - 1) as u64) as usize;
+
+        if bucket2 >= bucket1 {
+            (bucket1, bucket2 + 1)
+        } else {
+            (bucket1, bucket2)
+        }
+    }
+
+    /// Returns the label of the palette for the item, picked by the first hash value of the item
+    /// with [`Hash64::pick_label`]. The same item always gets the same label. Returns `None` if
+    /// the palette is empty.
+    fn label_of<'a, T: Hash, L>(&self, item: T, palette: &'a [L]) -> Option<&'a L>
+    where
+        Self::Hasher: HasherExt,
+    {
+        self.hashes_one(item)
+            .next()
+            .expect("the hash sequence is infinite")
+            .pick_label(palette)
+    }
+
+    /// Maps the first `k` hash values of the item to bit positions in a bitset of `m_bits` bits
+    /// backed by `u64` words. For each position it returns the index of the word and the mask
+    /// of the bit within that word, that is `(index / 64, 1 << (index % 64))`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `m_bits` is zero.
+    fn word_bits<T: Hash>(&self, item: T, m_bits: usize, k: usize) -> Vec<(usize, u64)>
+    where
+        Self::Hasher: HasherExt,
+    {
+        assert!(m_bits > 0, "the bitset must have at least one bit");
+
+        self.hashes_one(item)
+            .take(k)
+            .map(|h| {
+                let index = (u64::from(h) % m_bits as u64) as usize;
+                (index / 64, 1 << (index % 64))
+            })
+            .collect()
+    }
 }
 
 impl<T> BuildHasherExt for T
@@ -110,3 +595,814 @@ where
     <T as BuildHasher>::Hasher: HasherExt,
 {
 }
+
+/// Extends the sequences of hash values, e.g. the ones returned by [`HasherExt::finish_iter`]
+/// or [`BuildHasherExt::hashes_one`], with adaptors specific to hash streams.
+pub trait HashStreamExt: Iterator<Item = Hash64> + Sized {
+    /// Skips the first value of the sequence, which is the *base* value of the stream.
+    ///
+    /// In some double-hashing formulations the zeroth element is the raw value of the first
+    /// hasher, which may be used elsewhere (e.g. as a fingerprint) and therefore should not
+    /// be reused as one of the generated hash values. It is equivalent to `skip(1)`.
+    fn skip_base(self) -> impl Iterator<Item = Hash64> {
+        self.skip(1)
+    }
+
+    /// Groups the consecutive values of the sequence in pairs, `(e0, e1), (e2, e3), ...`,
+    /// for schemes which need two hash values per step, e.g. a bucket and a fingerprint.
+    fn group_pairs(mut self) -> impl Iterator<Item = (Hash64, Hash64)> {
+        std::iter::from_fn(move || Some((self.next()?, self.next()?)))
+    }
+
+    /// Calls a closure on each hash value as it flows through the sequence, e.g. to log
+    /// its low bits while tuning a data structure. It is a thin wrapper around [`Iterator::inspect`].
+    fn inspect_bits<F>(self, f: F) -> impl Iterator<Item = Hash64>
+    where
+        F: FnMut(&Hash64),
+    {
+        self.inspect(f)
+    }
+
+    /// Draws values from the sequence until each of the `0..m` buckets has been hit at least once,
+    /// and returns the number of draws. A value hits the bucket `value % m`. It is the
+    /// coupon-collector experiment, which takes about `m * ln(m)` draws on average.
+    /// For `m == 0` there is nothing to cover, and 0 is returned without drawing any value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `64 * m` draws in a row hit no new bucket. For a well mixed sequence this
+    /// happens with a probability below `e^-64`, but it is how a constant or short-period
+    /// sequence, which never covers all the buckets, is reported instead of looping forever.
+    fn draws_to_cover(mut self, m: usize) -> usize {
+        let mut covered = vec![false; m];
+        let mut remaining = m;
+        let mut draws = 0;
+        let mut stale = 0;
+
+        while remaining > 0 {
+            let h = self.next().expect("the hash sequence is infinite");
+            let bucket = (u64::from(h) % m as u64) as usize;
+            draws += 1;
+
+            if !covered[bucket] {
+                covered[bucket] = true;
+                remaining -= 1;
+                stale = 0;
+            } else {
+                stale += 1;
+                assert!(
+                    stale < 64 * m,
+                    "the sequence does not cover the {m} buckets"
+                );
+            }
+        }
+
+        draws
+    }
+
+    /// Reduces the first `n` values of the sequence into a single `u64`, e.g. for a compact
+    /// n-way fingerprint. Each value is folded with a rotate-xor-multiply step,
+    /// `acc = (acc.rotate_left(5) ^ h) * K` with an odd `K`, which is a bijection of both `acc`
+    /// and `h`, so changing any single one of the `n` values changes the result.
+    fn reduce_to_u64(self, n: usize) -> u64 {
+        const K: u64 = 0x9e37_79b9_7f4a_7c15;
+
+        self.take(n).fold(0, |acc, h| {
+            (acc.rotate_left(5) ^ u64::from(h)).wrapping_mul(K)
+        })
+    }
+
+    /// Combines each sliding window of `window` consecutive values of the sequence into one
+    /// hash value with [`Hash64::combine`], `e0..e(w-1), e1..ew, ...`. The values of a window are
+    /// folded from the first to the last one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is zero.
+    fn windows_combine(mut self, window: usize) -> impl Iterator<Item = Hash64> {
+        assert!(window > 0, "the window must be greater than zero");
+
+        let mut buffer = VecDeque::with_capacity(window);
+        std::iter::from_fn(move || {
+            if buffer.len() == window {
+                buffer.pop_front();
+            }
+
+            while buffer.len() < window {
+                buffer.push_back(self.next()?);
+            }
+
+            buffer.iter().copied().reduce(Hash64::combine)
+        })
+    }
+
+    /// Passes each value of the sequence through the SplitMix64 finalizer, for consumers which
+    /// need decorrelated values, e.g. sampling or sketches. The consecutive values of a double
+    /// hashing sequence differ by a slowly changing step, so their bits are strongly correlated;
+    /// the finalizer is a bijection which spreads each input bit over all the output bits, so
+    /// distinct values stay distinct.
+    fn finalized(self) -> impl Iterator<Item = Hash64> {
+        self.map(|h| splitmix::mix64(h.into()).into())
+    }
+
+    /// Guarantees that no value of the sequence is zero, e.g. for tables which use `0` as the
+    /// sentinel of an empty slot. A zero value is deterministically remapped to `1`, all the
+    /// other values are passed through unchanged, so the value `1` is produced with twice the
+    /// probability of any other value, which is `2^-63` per step.
+    fn non_zero(self) -> impl Iterator<Item = Hash64> {
+        self.map(|h| match u64::from(h) {
+            0 => Hash64::from(1),
+            _ => h,
+        })
+    }
+}
+
+impl<I> HashStreamExt for I where I: Iterator<Item = Hash64> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_one128() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        let hashes64 = builder
+            .hashes_one(item)
+            .take(2 * HASH_COUNT)
+            .collect::<Vec<_>>();
+        let hashes128 = builder
+            .hashes_one128(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        for (h128, pair) in hashes128.iter().zip(hashes64.chunks(2)) {
+            assert_eq!(h128.to_parts(), (pair[0], pair[1]));
+        }
+    }
+
+    #[test]
+    fn hashes_one_u32() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        let hashes64 = builder
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+        let hashes32 = builder
+            .hashes_one_u32(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        let expected = hashes64
+            .into_iter()
+            .map(Hash32::fold_from)
+            .collect::<Vec<_>>();
+        assert_eq!(hashes32, expected);
+        assert!(hashes32.windows(2).all(|w| w[0] != w[1]));
+    }
+
+    #[test]
+    fn finish_array() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let item = "Hello world!";
+
+        let mut hasher = builder.build_hasher();
+        item.hash(&mut hasher);
+        let hashes: [Hash64; 7] = hasher.finish_array();
+
+        let expected = builder.hashes_one(item).take(7).collect::<Vec<_>>();
+        assert_eq!(hashes.to_vec(), expected);
+
+        let hasher = builder.build_hasher();
+        assert_eq!(hasher.finish_array::<0>(), []);
+    }
+
+    #[test]
+    fn hashes_one_into() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let mut out = [Hash64::from(0); 7];
+
+        for item in ["Hello", "world"] {
+            builder.hashes_one_into(item, &mut out);
+
+            let expected = builder.hashes_one(item).take(out.len()).collect::<Vec<_>>();
+            assert_eq!(out.to_vec(), expected);
+        }
+
+        builder.hashes_one_into("Hello", &mut []);
+    }
+
+    #[test]
+    fn hashes_one_builds_one_hasher() {
+        use std::cell::Cell;
+
+        /// A builder which counts how many hashers it has built.
+        struct CountingBuilder<B> {
+            inner: B,
+            built: Cell<usize>,
+        }
+
+        impl<B: BuildHasher> BuildHasher for CountingBuilder<B> {
+            type Hasher = B::Hasher;
+
+            fn build_hasher(&self) -> Self::Hasher {
+                self.built.set(self.built.get() + 1);
+                self.inner.build_hasher()
+            }
+        }
+
+        let builder = CountingBuilder {
+            inner: BuildPairHasher::new_with_keys((0, 0), (1, 1)),
+            built: Cell::new(0),
+        };
+
+        assert_eq!(builder.hashes_one("Hello world!").take(7).count(), 7);
+        assert_eq!(builder.built.get(), 1);
+
+        assert_eq!(builder.hashes_one("Goodbye world!").take(100).count(), 100);
+        assert_eq!(builder.built.get(), 2);
+    }
+
+    #[test]
+    fn hashes_one_clone() {
+        let item = "Hello world!";
+
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let mut hashes = builder.hashes_one(item);
+        hashes.next();
+        let forked = hashes.clone();
+        assert!(hashes.take(10).eq(forked.take(10)));
+
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1)).with_sequence(TripleHashing);
+        let hashes = builder.hashes_one(item);
+        assert!(hashes.clone().take(10).eq(hashes.take(10)));
+
+        let builder = BuildMultiHasher::new_with_keys([(0, 0), (1, 1), (2, 2)]);
+        let hashes = builder.hashes_one(item);
+        assert!(hashes.clone().take(10).eq(hashes.take(10)));
+    }
+
+    #[test]
+    fn hashes_many() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        const K: usize = 5;
+
+        let items = ["Hello", "world", "!"];
+        let hashes = builder.hashes_many(items, K);
+        assert_eq!(hashes.len(), items.len() * K);
+
+        for (item, row) in items.iter().zip(hashes.chunks(K)) {
+            assert!(builder.hashes_one(item).take(K).eq(row.iter().copied()));
+        }
+
+        assert!(builder.hashes_many(items, 0).is_empty());
+        assert!(builder.hashes_many(Vec::<u64>::new(), K).is_empty());
+    }
+
+    #[test]
+    fn indices_one() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        const M: usize = 3;
+        const ITEMS: u64 = 30_000;
+
+        let mut counts = [0usize; M];
+        for item in 0..ITEMS {
+            for index in builder.indices_one(item, M).take(1) {
+                counts[index] += 1;
+            }
+        }
+
+        let expected = ITEMS as usize / M;
+        assert!(counts.iter().all(|&c| c.abs_diff(expected) < expected / 20));
+
+        let expected = builder
+            .hashes_one("Hello world!")
+            .take(10)
+            .map(|h| h.scale_to(1000) as usize)
+            .collect::<Vec<_>>();
+        let indices = builder
+            .indices_one("Hello world!", 1000)
+            .take(10)
+            .collect::<Vec<_>>();
+        assert_eq!(indices, expected);
+    }
+
+    #[test]
+    fn indices_one_distinct() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+
+        for (m, k) in [(1, 1), (4, 4), (8, 5), (1000, 7), (5, 0), (0, 0)] {
+            for item in 0..100 {
+                let indices = builder.indices_one_distinct(item, m, k);
+
+                assert_eq!(indices.len(), k);
+                assert!(indices.iter().all(|&i| i < m));
+                assert_eq!(indices.iter().collect::<HashSet<_>>().len(), k);
+            }
+        }
+
+        // Without collisions, the indices are the ones of the sequence.
+        let expected = builder
+            .indices_one("Hello world!", 1 << 20)
+            .take(7)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            builder.indices_one_distinct("Hello world!", 1 << 20, 7),
+            expected
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn indices_one_distinct_too_many() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        builder.indices_one_distinct("Hello world!", 3, 4);
+    }
+
+    #[test]
+    fn partitioned_indices() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+
+        for (m, k) in [(1000, 7), (10, 10), (100, 1), (5, 0)] {
+            for item in 0..100u64 {
+                let indices = builder.partitioned_indices(item, m, k).collect::<Vec<_>>();
+
+                assert_eq!(indices.len(), k);
+                for (i, index) in indices.iter().enumerate() {
+                    assert!((i * m / k..(i + 1) * m / k).contains(index));
+                }
+
+                assert!(builder.partitioned_indices(item, m, k).eq(indices));
+            }
+        }
+    }
+
+    #[test]
+    fn skip_base() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        let hashes1 = builder
+            .hashes_one(item)
+            .skip_base()
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+        let hashes2 = builder
+            .hashes_one(item)
+            .skip(1)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        assert_eq!(hashes1, hashes2);
+    }
+
+    #[test]
+    fn word_bits() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let item = "Hello world!";
+        const M_BITS: usize = 1000;
+        const K: usize = 7;
+
+        let expected = builder
+            .hashes_one(item)
+            .take(K)
+            .map(|h| {
+                let index = (u64::from(h) % M_BITS as u64) as usize;
+                (index / 64, 1u64 << (index % 64))
+            })
+            .collect::<Vec<_>>();
+
+        let word_bits = builder.word_bits(item, M_BITS, K);
+        assert_eq!(word_bits, expected);
+        assert!(word_bits
+            .iter()
+            .all(|(word, mask)| *word < M_BITS.div_ceil(64) && mask.count_ones() == 1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn word_bits_empty_bitset() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        builder.word_bits("Hello world!", 0, 0);
+    }
+
+    #[test]
+    fn hashes_from_byte_iter() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        const HASH_COUNT: usize = 10;
+
+        for len in [0, 1, 1023, 1024, 1025, 5000] {
+            let bytes = (0..len).map(|i| (i % 251) as u8);
+
+            let hashes1 = builder
+                .hashes_from_byte_iter(bytes.clone())
+                .take(HASH_COUNT)
+                .collect::<Vec<_>>();
+            let hashes2 = builder
+                .hashes_bytes(&bytes.collect::<Vec<_>>())
+                .take(HASH_COUNT)
+                .collect::<Vec<_>>();
+
+            assert_eq!(hashes1, hashes2);
+        }
+    }
+
+    #[test]
+    fn map_into() {
+        #[derive(Debug, PartialEq)]
+        struct BucketId(usize);
+
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let item = "Hello world!";
+        const BUCKETS: u64 = 100;
+        const HASH_COUNT: usize = 10;
+
+        let buckets = builder
+            .map_into(item, |h| BucketId((u64::from(h) % BUCKETS) as usize))
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        let expected = builder
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .map(|h| BucketId((u64::from(h) % BUCKETS) as usize))
+            .collect::<Vec<_>>();
+
+        assert_eq!(buckets, expected);
+    }
+
+    #[test]
+    fn k_index_set() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let item = "Hello world!";
+
+        for (k, modulus) in [
+            (0, 10),
+            (5, 10),
+            (10, 10),
+            (20, 10),
+            (3, 1),
+            (3, 0),
+            (7, 1000),
+        ] {
+            let indices = builder.k_index_set(item, k, modulus);
+
+            assert_eq!(indices.len(), k.min(modulus));
+            assert!(indices.iter().all(|&i| i < modulus));
+        }
+
+        // A single hasher generates a constant sequence.
+        let builder = BuildMultiHasher::new_with_keys([(0, 0)]);
+        let mut hashes = builder.hashes_one(item);
+        assert_eq!(hashes.next(), hashes.next());
+
+        for (k, modulus) in [(2, 10), (8, 8)] {
+            let indices = builder.k_index_set(item, k, modulus);
+            assert_eq!(indices.len(), k);
+            assert!(indices.iter().all(|&i| i < modulus));
+        }
+    }
+
+    #[test]
+    fn group_pairs() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let item = "Hello world!";
+        const PAIR_COUNT: usize = 5;
+
+        let pairs = builder
+            .hashes_one(item)
+            .group_pairs()
+            .take(PAIR_COUNT)
+            .collect::<Vec<_>>();
+
+        let mut hashes = builder.hashes_one(item);
+        for pair in pairs {
+            let first = hashes.next().unwrap();
+            let second = hashes.next().unwrap();
+            assert_eq!(pair, (first, second));
+        }
+
+        let odd = [1, 2, 3].map(Hash64::from).into_iter().group_pairs();
+        assert_eq!(
+            odd.collect::<Vec<_>>(),
+            vec![(Hash64::from(1), Hash64::from(2))]
+        );
+    }
+
+    #[test]
+    fn hashes_f64() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        const HASH_COUNT: usize = 10;
+
+        let hashes = |x: f64| builder.hashes_f64(x).take(HASH_COUNT).collect::<Vec<_>>();
+
+        assert_eq!(hashes(0.0), hashes(-0.0));
+
+        let nan1 = f64::NAN;
+        let nan2 = f64::from_bits(0x7ff8_0000_0000_0001);
+        let nan3 = -f64::NAN;
+        assert_ne!(nan1.to_bits(), nan2.to_bits());
+        assert_eq!(hashes(nan1), hashes(nan2));
+        assert_eq!(hashes(nan1), hashes(nan3));
+
+        assert_ne!(hashes(1.0), hashes(-1.0));
+        assert_ne!(hashes(0.0), hashes(f64::NAN));
+    }
+
+    #[test]
+    fn hashes_zero_sized() {
+        #[derive(Hash)]
+        struct Unit;
+
+        let builder1 = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let builder2 = BuildPairHasher::new_with_keys((2, 2), (3, 3));
+        const HASH_COUNT: usize = 10;
+
+        let unit1 = builder1.hashes_one(()).take(HASH_COUNT).collect::<Vec<_>>();
+        let unit2 = builder1.hashes_one(()).take(HASH_COUNT).collect::<Vec<_>>();
+        let unit_struct = builder1
+            .hashes_one(Unit)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+        let non_empty = builder1
+            .hashes_one("Hello world!")
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        // Zero-sized values write nothing, so their streams are seeded purely by the keys.
+        assert_eq!(unit1, unit2);
+        assert_eq!(unit1, unit_struct);
+        assert_ne!(unit1, non_empty);
+
+        let unit_keys2 = builder2.hashes_one(()).take(HASH_COUNT).collect::<Vec<_>>();
+        assert_ne!(unit1, unit_keys2);
+
+        // The stream does not degenerate into a constant sequence.
+        assert!(unit1.windows(2).all(|w| w[0] != w[1]));
+    }
+
+    #[test]
+    fn sample_indices() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+
+        for (n, k) in [(100, 10), (10, 10), (10, 20), (0, 5), (5, 0)] {
+            let sample = builder.sample_indices("seed", n, k);
+
+            assert_eq!(sample.len(), k.min(n));
+            assert!(sample.iter().all(|&i| i < n));
+            assert_eq!(sample.iter().collect::<HashSet<_>>().len(), sample.len());
+            assert_eq!(sample, builder.sample_indices("seed", n, k));
+        }
+
+        assert_ne!(
+            builder.sample_indices("seed1", 100, 10),
+            builder.sample_indices("seed2", 100, 10)
+        );
+    }
+
+    #[test]
+    fn permutation() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+
+        for n in [0, 1, 10, 100] {
+            let mut permutation = builder.permutation("seed", n);
+            assert_eq!(permutation, builder.permutation("seed", n));
+
+            permutation.sort_unstable();
+            assert_eq!(permutation, (0..n).collect::<Vec<_>>());
+        }
+
+        assert_ne!(
+            builder.permutation("seed1", 100),
+            builder.permutation("seed2", 100)
+        );
+    }
+
+    #[test]
+    fn geometric_level() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        const ITEMS: u64 = 10_000;
+
+        assert!((0..100u64).all(|item| builder.geometric_level(item, 0.0) == 0));
+
+        for p in [0.25, 0.5] {
+            let levels = (0..ITEMS)
+                .map(|item| builder.geometric_level(item, p))
+                .collect::<Vec<_>>();
+
+            for level in 1..=3 {
+                let observed = levels.iter().filter(|&&l| l >= level).count() as f64;
+                let expected = ITEMS as f64 * p.powi(level as i32);
+
+                assert!((observed - expected).abs() < 0.15 * expected + 20.0);
+            }
+        }
+    }
+
+    #[test]
+    fn two_choice_buckets() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+
+        assert_eq!(builder.two_choice_buckets("item", 1), (0, 0));
+
+        for m in [2, 3, 10, 1000] {
+            for item in 0..1000u64 {
+                let (bucket1, bucket2) = builder.two_choice_buckets(item, m);
+
+                assert!(bucket1 < m && bucket2 < m);
+                assert_ne!(bucket1, bucket2);
+                assert_eq!((bucket1, bucket2), builder.two_choice_buckets(item, m));
+            }
+        }
+    }
+
+    #[test]
+    fn label_of() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let palette = ["red", "green", "blue"];
+
+        for item in ["alpha", "beta", "gamma", "delta"] {
+            let label = builder.label_of(item, &palette);
+
+            assert!(label.is_some());
+            assert_eq!(label, builder.label_of(item, &palette));
+
+            let h = builder.hashes_one(item).next().unwrap();
+            assert_eq!(label, h.pick_label(&palette));
+        }
+
+        let empty: [&str; 0] = [];
+        assert_eq!(builder.label_of("alpha", &empty), None);
+    }
+
+    #[test]
+    fn inspect_bits() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        let mut observed = Vec::new();
+        let hashes = builder
+            .hashes_one(item)
+            .inspect_bits(|h| observed.push(*h))
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        assert_eq!(observed, hashes);
+    }
+
+    #[test]
+    fn hashes_byte_containers() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        const HASH_COUNT: usize = 10;
+
+        let vec = b"Hello world!".to_vec();
+        let slice = &vec[..];
+        let boxed = vec.clone().into_boxed_slice();
+
+        let from_vec = builder
+            .hashes_one(&vec)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+        let from_slice = builder
+            .hashes_one(slice)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+        let from_boxed = builder
+            .hashes_one(&boxed)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        assert_eq!(from_vec, from_slice);
+        assert_eq!(from_vec, from_boxed);
+
+        let bytes_vec = builder
+            .hashes_bytes(&vec)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+        let bytes_boxed = builder
+            .hashes_bytes(&boxed)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        assert_eq!(bytes_vec, bytes_boxed);
+        assert_ne!(bytes_vec, from_vec);
+    }
+
+    #[test]
+    fn draws_to_cover() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+
+        assert_eq!(builder.hashes_one("seed").draws_to_cover(0), 0);
+        assert_eq!(builder.hashes_one("seed").draws_to_cover(1), 1);
+
+        for m in [2, 5, 10, 20] {
+            let draws = builder.hashes_one("seed").draws_to_cover(m);
+
+            assert!(draws >= m);
+            assert!(draws < 20 * m);
+            assert_eq!(draws, builder.hashes_one("seed").draws_to_cover(m));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "does not cover")]
+    fn draws_to_cover_constant() {
+        std::iter::repeat(Hash64::from(7)).draws_to_cover(4);
+    }
+
+    #[test]
+    fn reduce_to_u64() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        const N: usize = 8;
+
+        let reduced = builder.hashes_one("Hello world!").reduce_to_u64(N);
+        assert_eq!(reduced, builder.hashes_one("Hello world!").reduce_to_u64(N));
+        assert_ne!(reduced, builder.hashes_one("Hello world?").reduce_to_u64(N));
+
+        let hashes = builder
+            .hashes_one("Hello world!")
+            .take(N + 1)
+            .collect::<Vec<_>>();
+        assert_eq!(hashes.iter().copied().reduce_to_u64(N), reduced);
+
+        for i in 0..N {
+            let mut changed = hashes.clone();
+            changed[i] = changed[i] + Hash64::from(1);
+            assert_ne!(changed.into_iter().reduce_to_u64(N), reduced);
+        }
+
+        let mut changed = hashes.clone();
+        changed[N] = changed[N] + Hash64::from(1);
+        assert_eq!(changed.into_iter().reduce_to_u64(N), reduced);
+    }
+
+    #[test]
+    fn windows_combine() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let item = "Hello world!";
+        const WINDOW: usize = 4;
+
+        let hashes = builder
+            .hashes_one(item)
+            .take(WINDOW + 1)
+            .collect::<Vec<_>>();
+        let combined = builder
+            .hashes_one(item)
+            .windows_combine(WINDOW)
+            .take(2)
+            .collect::<Vec<_>>();
+
+        let first = hashes[..WINDOW]
+            .iter()
+            .copied()
+            .reduce(Hash64::combine)
+            .unwrap();
+        let second = hashes[1..].iter().copied().reduce(Hash64::combine).unwrap();
+
+        assert_eq!(combined, vec![first, second]);
+    }
+
+    #[test]
+    fn finalized() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let item = "Hello world!";
+
+        let hashes = builder.hashes_one(item).take(10).collect::<Vec<_>>();
+        let finalized = builder.hashes_one(item).finalized().take(10);
+        assert!(finalized.eq(hashes.iter().map(|&h| splitmix::mix64(h.into()).into())));
+
+        // Double hashing values are in arithmetic progression, the finalized ones are not.
+        let second_diff = |h: &[Hash64]| u64::from(h[2] - h[1] - (h[1] - h[0]));
+        let hashes = KirschMitzenmacher
+            .sequence(1, 2, 0)
+            .take(3)
+            .collect::<Vec<_>>();
+        assert_eq!(second_diff(&hashes), 0);
+
+        let hashes = KirschMitzenmacher
+            .sequence(1, 2, 0)
+            .finalized()
+            .take(3)
+            .collect::<Vec<_>>();
+        assert_ne!(second_diff(&hashes), 0);
+    }
+
+    #[test]
+    fn non_zero() {
+        let hashes = [0, 1, 0, 42, u64::MAX].map(Hash64::from);
+        let non_zero = hashes.into_iter().non_zero().map(u64::from);
+        assert!(non_zero.eq([1, 1, 1, 42, u64::MAX]));
+
+        // With the zero mixing seed, a zero first base hash and a zero step, the default
+        // sequence starts with zero.
+        let hashes = DefaultSequence.sequence(0, 0, 0).non_zero().take(3);
+        assert!(hashes.map(u64::from).eq([1, 1, 2]));
+    }
+}
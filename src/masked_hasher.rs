@@ -0,0 +1,118 @@
+use std::hash::{BuildHasher, Hasher};
+
+/// A [`Hasher`] which xors a fixed mask into the result of an inner [`Hasher`].
+/// It provides a cheap way to separate the hash values of different tenants
+/// without rebuilding the inner hashers with different keys.
+///
+/// It can be used as one of the components of a [`BuildPairHasher`](crate::BuildPairHasher) through [`BuildMaskedHasher`].
+///
+/// NB: The mask does not change how the input is mixed, so two inputs which collide
+/// for one mask collide for every mask.
+#[derive(Clone)]
+pub struct MaskedHasher<H> {
+    hasher: H,
+    mask: u64,
+}
+
+impl<H> MaskedHasher<H> {
+    pub fn new(hasher: H, mask: u64) -> Self {
+        Self { hasher, mask }
+    }
+}
+
+impl<H> Hasher for MaskedHasher<H>
+where
+    H: Hasher,
+{
+    fn finish(&self) -> u64 {
+        self.hasher.finish() ^ self.mask
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.hasher.write(bytes);
+    }
+}
+
+/// An instance of [`BuildHasher`] trait which builds [`MaskedHasher`] instances
+/// with the same mask.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+/// use std::collections::hash_map::RandomState;
+///
+/// let tenant = 42;
+/// let builder1 = BuildMaskedHasher::new(RandomState::new(), tenant);
+/// let builder2 = BuildMaskedHasher::new(RandomState::new(), tenant);
+/// let builder = BuildPairHasher::new(builder1, builder2);
+///
+/// let hashes = builder.hashes_one("Hello world!").take(10).collect::<Vec<_>>();
+/// assert_eq!(hashes.len(), 10);
+///```
+pub struct BuildMaskedHasher<B> {
+    builder: B,
+    mask: u64,
+}
+
+impl<B> BuildMaskedHasher<B> {
+    pub fn new(builder: B, mask: u64) -> Self {
+        Self { builder, mask }
+    }
+}
+
+impl<B> BuildHasher for BuildMaskedHasher<B>
+where
+    B: BuildHasher,
+{
+    type Hasher = MaskedHasher<B::Hasher>;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        MaskedHasher::new(self.builder.build_hasher(), self.mask)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{build_sip_hasher::BuildSipHasher, BuildHasherExt, BuildPairHasher};
+
+    fn build(
+        mask: u64,
+    ) -> BuildPairHasher<BuildMaskedHasher<BuildSipHasher>, BuildMaskedHasher<BuildSipHasher>> {
+        let builder1 = BuildMaskedHasher::new(BuildSipHasher::from((0, 0)), mask);
+        let builder2 = BuildMaskedHasher::new(BuildSipHasher::from((1, 1)), mask);
+        BuildPairHasher::new(builder1, builder2)
+    }
+
+    #[test]
+    fn different_masks() {
+        let item = "Hello world!";
+        const HASH_COUNT: usize = 10;
+
+        let hashes1 = build(1)
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+        let hashes2 = build(2)
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+        let hashes3 = build(1)
+            .hashes_one(item)
+            .take(HASH_COUNT)
+            .collect::<Vec<_>>();
+
+        assert_ne!(hashes1, hashes2);
+        assert_eq!(hashes1, hashes3);
+    }
+
+    #[test]
+    fn zero_mask() {
+        let item = "Hello world!";
+
+        let masked = BuildMaskedHasher::new(BuildSipHasher::from((0, 0)), 0).hash_one(item);
+        let plain = BuildSipHasher::from((0, 0)).hash_one(item);
+        assert_eq!(masked, plain);
+    }
+}
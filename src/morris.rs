@@ -0,0 +1,80 @@
+use crate::{BuildHasherExt, HasherExt};
+use std::hash::Hash;
+
+/// Increments a Morris approximate counter. The counter is incremented with probability
+/// `2^-counter`, using the first hash value of the `token` as the coin: the increment happens
+/// when the lowest `counter` bits of the hash are all zero.
+///
+/// Each increment should use a different token (e.g. the event being counted), otherwise the
+/// same coin is flipped again and again. The number of counted events can be estimated with
+/// [`morris_estimate`].
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+///
+/// let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+/// let mut counter = 0u8;
+///
+/// for event in 0..1000 {
+///     morris_increment(&mut counter, &builder, &event);
+/// }
+///
+/// assert!(morris_estimate(counter) > 0.0);
+///```
+pub fn morris_increment<B, T>(counter: &mut u8, builder: &B, token: &T)
+where
+    B: BuildHasherExt,
+    B::Hasher: HasherExt,
+    T: Hash + ?Sized,
+{
+    let h = builder
+        .hashes_one(token)
+        .next()
+        .expect("the hash sequence is infinite");
+
+    if u64::from(h).trailing_zeros() >= *counter as u32 {
+        *counter = counter.saturating_add(1);
+    }
+}
+
+/// Decodes the value of a Morris approximate counter, that is `2^counter - 1`.
+pub fn morris_estimate(counter: u8) -> f64 {
+    2f64.powi(counter as i32) - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BuildPairHasher;
+
+    #[test]
+    fn estimate() {
+        assert_eq!(morris_estimate(0), 0.0);
+        assert_eq!(morris_estimate(1), 1.0);
+        assert_eq!(morris_estimate(10), 1023.0);
+    }
+
+    #[test]
+    fn tracks_count() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        const COUNTERS: usize = 200;
+        const EVENTS: usize = 1000;
+
+        // A single counter has a large variance, so the estimates of many counters are averaged.
+        let mean = (0..COUNTERS)
+            .map(|id| {
+                let mut counter = 0u8;
+                for event in 0..EVENTS {
+                    morris_increment(&mut counter, &builder, &(id, event));
+                }
+                morris_estimate(counter)
+            })
+            .sum::<f64>()
+            / COUNTERS as f64;
+
+        let error = (mean - EVENTS as f64).abs() / EVENTS as f64;
+        assert!(error < 0.2, "mean estimate {mean} for {EVENTS} events");
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{Hash64, HasherExt};
+use std::hash::Hasher;
+
+/// A [`Hasher`] which combines `N` [`Hasher`] instances, generalizing the
+/// [`PairHasher`](crate::PairHasher) combinator to more than two base hash values.
+///
+/// With `h[0], ..., h[N-1]` the results of the inner hashers, the sequence of hash values is
+/// generated by repeated forward differences: the first value is `h[0]`, and after each step
+/// `h[j] += h[j + 1]` for all `j` but the last one (all wrapping). So the `i`-th value is a
+/// polynomial of degree `N - 1` in `i` whose coefficients are driven by the `N` base hashes,
+/// e.g. for `N = 2` it is the classic double hashing `h[0] + i * h[1]`.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+/// use std::hash::{BuildHasher, Hash};
+///
+/// let builder = BuildMultiHasher::new_with_keys([(0, 0), (1, 1), (2, 2)]);
+/// let mut hasher = builder.build_hasher();
+///
+/// "Hello world".hash(&mut hasher);
+///
+/// const HASHES_COUNT: usize = 10;
+/// let hashes = hasher.finish_iter().take(HASHES_COUNT).collect::<Vec<_>>();
+/// assert_eq!(hashes.len(), HASHES_COUNT);
+///```
+pub struct MultiHasher<H, const N: usize> {
+    hashers: [H; N],
+}
+
+impl<H, const N: usize> MultiHasher<H, N> {
+    /// Creates a new combinator from `N` hashers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    pub fn new(hashers: [H; N]) -> Self {
+        assert!(N > 0, "at least one hasher is required");
+        Self { hashers }
+    }
+}
+
+impl<H, const N: usize> Hasher for MultiHasher<H, N>
+where
+    H: Hasher,
+{
+    fn finish(&self) -> u64 {
+        self.hashers
+            .iter()
+            .fold(0u64, |acc, hasher| acc.wrapping_add(hasher.finish()))
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for hasher in self.hashers.iter_mut() {
+            hasher.write(bytes);
+        }
+    }
+}
+
+impl<H, const N: usize> HasherExt for MultiHasher<H, N>
+where
+    H: Hasher,
+{
+    fn finish_iter(self) -> impl Iterator<Item = Hash64> + Clone {
+        let differences = self.hashers.map(|hasher| hasher.finish());
+        MultiHasherIterator { differences }
+    }
+}
+
+#[derive(Clone)]
+struct MultiHasherIterator<const N: usize> {
+    differences: [u64; N],
+}
+
+impl<const N: usize> Iterator for MultiHasherIterator<N> {
+    type Item = Hash64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ret = self.differences[0];
+
+        for j in 1..N {
+            self.differences[j - 1] = self.differences[j - 1].wrapping_add(self.differences[j]);
+        }
+
+        Some(ret.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use siphasher::sip::SipHasher;
+    use std::hash::Hash;
+
+    fn hasher<const N: usize>() -> MultiHasher<SipHasher, N> {
+        MultiHasher::new(std::array::from_fn(|i| {
+            SipHasher::new_with_keys(i as u64, i as u64)
+        }))
+    }
+
+    #[test]
+    fn hash_finish_iter() {
+        let mut hasher = hasher::<4>();
+        "Hello world!".hash(&mut hasher);
+
+        const HASHES_COUNT: usize = 10;
+        let hashes = hasher.finish_iter().take(HASHES_COUNT).collect::<Vec<_>>();
+
+        assert_eq!(hashes.len(), HASHES_COUNT);
+        assert!(hashes.windows(2).all(|w| w[0] != w[1]));
+    }
+
+    #[test]
+    fn forward_differences() {
+        let mut hasher = hasher::<3>();
+        "Hello world!".hash(&mut hasher);
+
+        let [a, b, c] = hasher.hashers.each_ref().map(|h| h.finish());
+        let hashes = hasher.finish_iter().take(10).map(u64::from);
+
+        // With three hashers, the i-th value is a + i * b + i * (i - 1) / 2 * c.
+        for (i, h) in (0u64..).zip(hashes) {
+            let expected = a
+                .wrapping_add(i.wrapping_mul(b))
+                .wrapping_add((i * i.saturating_sub(1) / 2).wrapping_mul(c));
+            assert_eq!(h, expected);
+        }
+    }
+
+    #[test]
+    fn single_hasher() {
+        let mut hasher = hasher::<1>();
+        "Hello world!".hash(&mut hasher);
+
+        let finish = hasher.finish();
+        assert!(hasher.finish_iter().take(5).all(|h| u64::from(h) == finish));
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{BuildHasherExt, Hash64, HasherExt};
+use std::hash::Hash;
+
+/// Computes an order-independent hash for a multiset of items. The result is the wrapping
+/// sum of the first hash value of each item, so permutations of the same items produce the
+/// same hash, while changing the multiplicity of an item changes the result.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+///
+/// let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+///
+/// let h1 = multiset_hash(&builder, ["a", "b", "c"]);
+/// let h2 = multiset_hash(&builder, ["c", "a", "b"]);
+/// assert_eq!(h1, h2);
+///```
+pub fn multiset_hash<B, T, I>(builder: &B, items: I) -> Hash64
+where
+    B: BuildHasherExt,
+    B::Hasher: HasherExt,
+    T: Hash,
+    I: IntoIterator<Item = T>,
+{
+    items
+        .into_iter()
+        .map(|item| {
+            builder
+                .hashes_one(item)
+                .next()
+                .expect("the hash sequence is infinite")
+        })
+        .fold(Hash64::from(0), |acc, h| acc + h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BuildPairHasher;
+
+    #[test]
+    fn order_invariance() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+
+        let h1 = multiset_hash(&builder, ["a", "b", "b", "c"]);
+        let h2 = multiset_hash(&builder, ["b", "c", "b", "a"]);
+        let h3 = multiset_hash(&builder, vec!["c", "b", "a", "b"]);
+
+        assert_eq!(h1, h2);
+        assert_eq!(h1, h3);
+    }
+
+    #[test]
+    fn multiplicity_sensitivity() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+
+        let h1 = multiset_hash(&builder, ["a", "b", "c"]);
+        let h2 = multiset_hash(&builder, ["a", "b", "b", "c"]);
+        let h3 = multiset_hash(&builder, ["a", "a", "b", "c"]);
+
+        assert_ne!(h1, h2);
+        assert_ne!(h1, h3);
+        assert_ne!(h2, h3);
+    }
+
+    #[test]
+    fn empty_multiset() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let items: [&str; 0] = [];
+
+        assert_eq!(multiset_hash(&builder, items), Hash64::from(0));
+    }
+}
@@ -1,9 +1,15 @@
-use crate::{Hash64, HasherExt};
+use crate::{DefaultSequence, Hash64, HashSeq, HashSequence, HasherExt};
+use siphasher::sip::SipHasher;
 use std::hash::Hasher;
 
 /// A [`Hasher`] which combines two [`Hasher`] instances. The hasher combinator
 /// uses the two [`Hasher`] instances to generate sequences of hash values.
 ///
+/// Calling [`Hasher::finish`] does not change the state of the hasher, since it only borrows
+/// the hasher and its two components immutably. The hasher can be finished any number of times
+/// and then consumed by [`HasherExt::finish_iter`], which produces the same sequence as if
+/// `finish` was never called.
+///
 /// # Example
 ///
 ///```
@@ -21,18 +27,118 @@ use std::hash::Hasher;
 /// let hashes = hasher.finish_iter().take(HASHES_COUNT).collect::<Vec<_>>();
 /// assert!(hashes.into_iter().all(|h| h != Hash64::from(0)));
 ///```
-pub struct PairHasher<H1, H2> {
+#[derive(Clone)]
+pub struct PairHasher<H1, H2, S = DefaultSequence> {
     hasher1: H1,
     hasher2: H2,
+    mix_seed: u64,
+    sequence: S,
 }
 
 impl<H1, H2> PairHasher<H1, H2> {
     pub(crate) fn new(hasher1: H1, hasher2: H2) -> Self {
-        Self { hasher1, hasher2 }
+        Self {
+            hasher1,
+            hasher2,
+            mix_seed: 0,
+            sequence: DefaultSequence,
+        }
+    }
+}
+
+impl<H1, H2, S> PairHasher<H1, H2, S> {
+    pub(crate) fn with_mix_seed(self, mix_seed: u64) -> Self {
+        Self { mix_seed, ..self }
+    }
+
+    pub(crate) fn with_sequence<T>(self, sequence: T) -> PairHasher<H1, H2, T> {
+        PairHasher {
+            hasher1: self.hasher1,
+            hasher2: self.hasher2,
+            mix_seed: self.mix_seed,
+            sequence,
+        }
+    }
+}
+
+impl<H1, H2, S> PairHasher<H1, H2, S>
+where
+    H1: Clone,
+    H2: Clone,
+    S: Clone,
+{
+    /// Returns a copy of the hasher with the same state, e.g. after hashing a long common
+    /// prefix, so the prefix is hashed only once and each fork only hashes its own suffix.
+    ///
+    /// # Example
+    ///
+    ///```
+    /// use aabel_multihash_rs::*;
+    /// use std::hash::{BuildHasher, Hash};
+    ///
+    /// let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+    ///
+    /// let mut prefix = builder.build_hasher();
+    /// ("namespace", 2u32).hash(&mut prefix);
+    ///
+    /// for suffix in ["a", "b", "c"] {
+    ///     let mut hasher = prefix.fork();
+    ///     suffix.hash(&mut hasher);
+    ///
+    ///     let hashes = hasher.finish_iter().take(5);
+    ///     assert!(hashes.eq(builder.hashes_one(("namespace", 2u32, suffix)).take(5)));
+    /// }
+    ///```
+    pub fn fork(&self) -> Self {
+        self.clone()
+    }
+}
+
+impl<S> PairHasher<SipHasher, SipHasher, S> {
+    /// Restores the initial keyed state of the two hashers, discarding all the data written so
+    /// far, so a single hasher can be reused across items in a tight loop instead of building
+    /// a new one per item. The mixing seed and the sequence strategy are kept.
+    ///
+    /// # Example
+    ///
+    ///```
+    /// use aabel_multihash_rs::*;
+    /// use std::hash::{BuildHasher, Hash, Hasher};
+    ///
+    /// let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+    /// let mut hasher = builder.build_hasher();
+    ///
+    /// for item in ["Hello", "world"] {
+    ///     hasher.reset();
+    ///     item.hash(&mut hasher);
+    ///     assert_eq!(hasher.finish(), builder.hash_one(item));
+    /// }
+    ///```
+    pub fn reset(&mut self) {
+        let (key0, key1) = self.hasher1.keys();
+        self.hasher1 = SipHasher::new_with_keys(key0, key1);
+
+        let (key0, key1) = self.hasher2.keys();
+        self.hasher2 = SipHasher::new_with_keys(key0, key1);
+    }
+}
+
+impl<H1, H2, S> PairHasher<H1, H2, S>
+where
+    H1: Hasher,
+    H2: Hasher,
+{
+    /// Consumes the hasher and returns its base hashes as a [`HashSeq`], whose sequence of
+    /// hash values can be iterated any number of times.
+    pub fn finish_seq(self) -> HashSeq<S> {
+        let a = self.hasher1.finish();
+        let b = self.hasher2.finish();
+
+        HashSeq::new_with_sequence(a, b, self.mix_seed, self.sequence)
     }
 }
 
-impl<H1, H2> Hasher for PairHasher<H1, H2>
+impl<H1, H2, S> Hasher for PairHasher<H1, H2, S>
 where
     H1: Hasher,
     H2: Hasher,
@@ -49,52 +155,27 @@ where
     }
 }
 
-impl<H1, H2> HasherExt for PairHasher<H1, H2>
+impl<H1, H2, S> HasherExt for PairHasher<H1, H2, S>
 where
     H1: Hasher,
     H2: Hasher,
+    S: HashSequence,
 {
-    fn finish_iter(self) -> impl Iterator<Item = Hash64> {
+    fn finish_iter(self) -> impl Iterator<Item = Hash64> + Clone {
         let a = self.hasher1.finish();
         let b = self.hasher2.finish();
 
-        PairHasherIterator::new(a, b)
-    }
-}
-
-pub(crate) struct PairHasherIterator {
-    a: u64,
-    b: u64,
-    c: u64,
-}
-
-impl PairHasherIterator {
-    pub(crate) fn new(a: u64, b: u64) -> Self {
-        Self {
-            a,
-            b,
-            c: Default::default(),
-        }
+        self.sequence.sequence(a, b, self.mix_seed)
     }
-}
-
-impl Iterator for PairHasherIterator {
-    type Item = Hash64;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let ret = self.a;
-        self.a = self.a.wrapping_add(self.b);
-        self.b = self.b.wrapping_add(self.c);
-        self.c += self.c.wrapping_add(1);
 
-        Some(ret.into())
+    fn finish_pair(self) -> (u64, u64) {
+        (self.hasher1.finish(), self.hasher2.finish())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use siphasher::sip::SipHasher;
     use std::hash::Hash;
 
     #[test]
@@ -127,4 +208,168 @@ mod tests {
         let hashes = hasher.finish_iter().take(HASHES_COUNT).collect::<Vec<_>>();
         assert!(hashes.into_iter().all(|h| h != Hash64::from(0)))
     }
+
+    #[test]
+    fn finish_does_not_change_finish_iter() {
+        let item = "Hello world!";
+        const HASHES_COUNT: usize = 10;
+
+        let build = || {
+            let hasher1 = SipHasher::new_with_keys(0, 0);
+            let hasher2 = SipHasher::new_with_keys(1, 1);
+            let mut hasher = PairHasher::new(hasher1, hasher2);
+            item.hash(&mut hasher);
+            hasher
+        };
+
+        let expected = build().finish_iter().take(HASHES_COUNT).collect::<Vec<_>>();
+
+        for finish_count in 1..5 {
+            let hasher = build();
+            let finishes = (0..finish_count)
+                .map(|_| hasher.finish())
+                .collect::<Vec<_>>();
+            assert!(finishes.iter().all(|&h| h == finishes[0]));
+
+            let hashes = hasher.finish_iter().take(HASHES_COUNT).collect::<Vec<_>>();
+            assert_eq!(hashes, expected);
+        }
+    }
+
+    #[test]
+    fn finish_seq() {
+        let build = || {
+            let hasher1 = SipHasher::new_with_keys(0, 0);
+            let hasher2 = SipHasher::new_with_keys(1, 1);
+            let mut hasher = PairHasher::new(hasher1, hasher2).with_mix_seed(42);
+            "Hello world!".hash(&mut hasher);
+            hasher
+        };
+
+        let seq = build().finish_seq();
+        let expected = build().finish_iter().take(10).collect::<Vec<_>>();
+
+        assert_eq!(seq.base(), build().finish_pair());
+        for _ in 0..2 {
+            assert_eq!(seq.iter().take(10).collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn fork() {
+        let mut prefix = PairHasher::new(
+            SipHasher::new_with_keys(0, 0),
+            SipHasher::new_with_keys(1, 1),
+        )
+        .with_mix_seed(42);
+        "prefix".hash(&mut prefix);
+
+        let mut fork1 = prefix.fork();
+        let mut fork2 = prefix.fork();
+        "suffix1".hash(&mut fork1);
+        "suffix2".hash(&mut fork2);
+
+        let mut expected = PairHasher::new(
+            SipHasher::new_with_keys(0, 0),
+            SipHasher::new_with_keys(1, 1),
+        )
+        .with_mix_seed(42);
+        ("prefix", "suffix1").hash(&mut expected);
+
+        assert_eq!(fork1.finish(), expected.finish());
+        assert_ne!(fork1.finish(), fork2.finish());
+        assert!(fork1
+            .finish_iter()
+            .take(10)
+            .eq(expected.finish_iter().take(10)));
+
+        // Forking leaves the original hasher untouched.
+        let mut unforked = PairHasher::new(
+            SipHasher::new_with_keys(0, 0),
+            SipHasher::new_with_keys(1, 1),
+        );
+        "prefix".hash(&mut unforked);
+        assert_eq!(prefix.finish(), unforked.finish());
+    }
+
+    #[test]
+    fn reset() {
+        let build = || {
+            PairHasher::new(
+                SipHasher::new_with_keys(0, 0),
+                SipHasher::new_with_keys(1, 1),
+            )
+            .with_mix_seed(42)
+        };
+
+        let mut hasher = build();
+        "Hello world!".hash(&mut hasher);
+        let first = hasher.finish();
+
+        hasher.reset();
+        assert_eq!(hasher.finish(), build().finish());
+
+        "Hello world!".hash(&mut hasher);
+        assert_eq!(hasher.finish(), first);
+
+        let mut expected = build();
+        "Hello world!".hash(&mut expected);
+        assert!(hasher
+            .finish_iter()
+            .take(10)
+            .eq(expected.finish_iter().take(10)));
+    }
+
+    #[test]
+    fn finish_iter_ref() {
+        let build = || {
+            PairHasher::new(
+                SipHasher::new_with_keys(0, 0),
+                SipHasher::new_with_keys(1, 1),
+            )
+            .with_mix_seed(42)
+        };
+
+        let mut hasher = build();
+        "stage1".hash(&mut hasher);
+        let stage1 = hasher.finish_iter_ref().take(10);
+
+        // The hasher can still be written while the first sequence is alive.
+        "stage2".hash(&mut hasher);
+        let stage2 = hasher.finish_iter_ref().take(10);
+
+        let mut expected = build();
+        "stage1".hash(&mut expected);
+        assert!(stage1.eq(expected.clone().finish_iter().take(10)));
+
+        "stage2".hash(&mut expected);
+        assert!(stage2.eq(expected.finish_iter().take(10)));
+        assert!(hasher
+            .finish_iter_ref()
+            .take(10)
+            .eq(hasher.finish_iter().take(10)));
+    }
+
+    #[test]
+    fn finish_pair() {
+        let build = || {
+            let hasher1 = SipHasher::new_with_keys(0, 0);
+            let hasher2 = SipHasher::new_with_keys(1, 1);
+            let mut hasher = PairHasher::new(hasher1, hasher2).with_mix_seed(42);
+            "Hello world!".hash(&mut hasher);
+            hasher
+        };
+
+        let hasher = build();
+        let expected = (hasher.hasher1.finish(), hasher.hasher2.finish());
+        assert_eq!(hasher.finish_pair(), expected);
+
+        // The first value of the sequence is the first base hash, and the first step is
+        // the second base hash forced to be odd.
+        let [e0, e1] = build().finish_array();
+        assert_eq!(
+            (e0.into(), u64::from(e1 - e0)),
+            (expected.0, expected.1 | 1)
+        );
+    }
 }
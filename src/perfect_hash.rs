@@ -0,0 +1,92 @@
+use crate::{
+    build_sip_hasher::SipHasherKeys, splitmix::SplitMix64, BuildHasherExt, BuildPairHasher,
+};
+use std::hash::Hash;
+
+/// Searches for a pair of [`SipHasherKeys`] for which the first hash value of each key,
+/// reduced modulo `keys.len()`, is unique. In other words the keys are mapped by a
+/// [`BuildPairHasher`] built with the returned keys into `0..keys.len()` without collisions.
+///
+/// The collision-free hash is the first value of [`BuildHasherExt::hashes_one`], which is the
+/// result of the first SipHash hasher alone, so it only depends on the first keys of the pair;
+/// the second keys are arbitrary. It is *not* [`std::hash::BuildHasher::hash_one`], which returns
+/// the sum of the two hashers and may collide.
+///
+/// The search tries the key pairs drawn from a SplitMix64 generator with a fixed seed, so the
+/// same keys are always found for the same set, and gives up after `max_tries` attempts, in
+/// which case it returns `None`. It is intended for small static sets; the expected number of
+/// tries grows exponentially with the size of the set.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+///
+/// let keys = ["a", "b", "c", "d"];
+/// let (keys1, keys2) = find_perfect_keys(&keys, 10_000).unwrap();
+///
+/// let builder = BuildPairHasher::new_with_keys(keys1, keys2);
+/// let mut slots = keys
+///     .iter()
+///     .map(|key| u64::from(builder.hashes_one(key).next().unwrap()) % keys.len() as u64)
+///     .collect::<Vec<_>>();
+/// slots.sort();
+/// assert_eq!(slots, vec![0, 1, 2, 3]);
+///```
+pub fn find_perfect_keys<T: Hash>(
+    keys: &[T],
+    max_tries: usize,
+) -> Option<(SipHasherKeys, SipHasherKeys)> {
+    let n = keys.len() as u64;
+    let mut rng = SplitMix64::new(0);
+    let mut used = vec![false; keys.len()];
+
+    for _ in 0..max_tries {
+        let keys1: SipHasherKeys = (rng.next_u64(), rng.next_u64());
+        let keys2: SipHasherKeys = (rng.next_u64(), rng.next_u64());
+        let builder = BuildPairHasher::new_with_keys(keys1, keys2);
+
+        used.iter_mut().for_each(|u| *u = false);
+        let injective = keys.iter().all(|key| {
+            let hash = builder
+                .hashes_one(key)
+                .next()
+                .expect("the hash sequence is infinite");
+            let slot = (u64::from(hash) % n) as usize;
+
+            !std::mem::replace(&mut used[slot], true)
+        });
+
+        if injective {
+            return Some((keys1, keys2));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_perfect_keys() {
+        let keys = ["alpha", "beta", "gamma", "delta", "epsilon"];
+
+        let (keys1, keys2) = find_perfect_keys(&keys, 100_000).unwrap();
+
+        let builder = BuildPairHasher::new_with_keys(keys1, keys2);
+        let mut slots = keys
+            .iter()
+            .map(|key| u64::from(builder.hashes_one(key).next().unwrap()) % keys.len() as u64)
+            .collect::<Vec<_>>();
+        slots.sort();
+        assert_eq!(slots, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn duplicated_keys() {
+        let keys = ["alpha", "alpha"];
+        assert!(find_perfect_keys(&keys, 100).is_none());
+    }
+}
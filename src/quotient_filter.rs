@@ -0,0 +1,385 @@
+use crate::{BuildHasherExt, HasherExt};
+use std::{hash::Hash, marker::PhantomData};
+
+/// A slot of the quotient filter, holding a remainder and the three metadata bits.
+#[derive(Debug, Default, Clone, Copy)]
+struct Slot {
+    remainder: u64,
+    /// The slot is the canonical slot of at least one stored remainder.
+    occupied: bool,
+    /// The remainder belongs to the same run as the one in the previous slot.
+    continuation: bool,
+    /// The remainder is not stored in its canonical slot.
+    shifted: bool,
+}
+
+impl Slot {
+    fn is_empty(&self) -> bool {
+        !self.occupied && !self.continuation && !self.shifted
+    }
+}
+
+/// A quotient filter, an approximate membership structure which supports deletions.
+///
+/// The first hash value of an item is split with [`Hash64::div_rem`](crate::Hash64::div_rem):
+/// the low `q_bits` bits (the remainder of the division) select the canonical slot, while the
+/// next `r_bits` high bits (the quotient of the division) are stored as the fingerprint.
+/// Fingerprints sharing the same canonical slot are kept in contiguous *runs*, and the runs
+/// are tracked with the *occupied*, *continuation* and *shifted* metadata bits.
+///
+/// The filter behaves as a multiset of fingerprints: inserting the same item twice takes two
+/// slots, and deleting it once keeps it in the filter.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+///
+/// let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+/// let mut filter = QuotientFilter::new(builder, 8, 16);
+///
+/// assert!(filter.insert("Hello world!"));
+/// assert!(filter.contains("Hello world!"));
+///
+/// assert!(filter.delete("Hello world!"));
+/// assert!(!filter.contains("Hello world!"));
+///```
+pub struct QuotientFilter<T: ?Sized, B> {
+    builder: B,
+    q_bits: u32,
+    r_bits: u32,
+    slots: Vec<Slot>,
+    len: usize,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<T, B> QuotientFilter<T, B>
+where
+    T: Hash + ?Sized,
+    B: BuildHasherExt,
+    B::Hasher: HasherExt,
+{
+    /// Creates a new filter with `2^q_bits` slots, each storing an `r_bits` wide fingerprint.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of `q_bits` or `r_bits` is zero, or if `q_bits + r_bits` is greater than 64.
+    pub fn new(builder: B, q_bits: u32, r_bits: u32) -> Self {
+        assert!(q_bits > 0, "the quotient must have at least one bit");
+        assert!(r_bits > 0, "the remainder must have at least one bit");
+        assert!(
+            q_bits + r_bits <= 64,
+            "the fingerprint must fit into 64 bits"
+        );
+
+        Self {
+            builder,
+            q_bits,
+            r_bits,
+            slots: vec![Slot::default(); 1 << q_bits],
+            len: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of fingerprints stored in the filter.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the filter stores no fingerprints.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of slots of the filter, which is the maximum number of fingerprints it can store.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Inserts an item into the filter. Returns `false` if the filter is full.
+    pub fn insert(&mut self, item: &T) -> bool {
+        if self.len == self.capacity() {
+            return false;
+        }
+
+        let (fq, fr) = self.fingerprint(item);
+        self.insert_fingerprint(fq, fr);
+        self.len += 1;
+        true
+    }
+
+    /// Returns `true` if the item may be in the filter, and `false` if it is definitely not.
+    pub fn contains(&self, item: &T) -> bool {
+        let (fq, fr) = self.fingerprint(item);
+        self.find(fq, fr).is_some()
+    }
+
+    /// Deletes an item from the filter. Returns `false` if the item was not found.
+    ///
+    /// NB: Deleting an item that was never inserted may remove the fingerprint of
+    /// a different item which collides with it.
+    pub fn delete(&mut self, item: &T) -> bool {
+        let (fq, fr) = self.fingerprint(item);
+        if self.find(fq, fr).is_none() {
+            return false;
+        }
+
+        // Rebuild the cluster which holds the fingerprint without it.
+        let mut start = fq;
+        while self.slots[start].shifted {
+            start = self.decr(start);
+        }
+
+        let mut entries = self.cluster_entries(start);
+
+        let mut s = start;
+        for _ in 0..entries.len() {
+            self.slots[s] = Slot::default();
+            s = self.incr(s);
+        }
+
+        let pos = entries
+            .iter()
+            .position(|&entry| entry == (fq, fr))
+            .expect("the fingerprint was found in the cluster");
+        entries.swap_remove(pos);
+
+        for (q, r) in entries {
+            self.insert_fingerprint(q, r);
+        }
+
+        self.len -= 1;
+        true
+    }
+
+    /// Splits the first hash value of the item into the canonical slot and the fingerprint.
+    fn fingerprint(&self, item: &T) -> (usize, u64) {
+        let h = self
+            .builder
+            .hashes_one(item)
+            .next()
+            .expect("the hash sequence is infinite");
+        let (quotient, remainder) = h.div_rem(1 << self.q_bits);
+        let mask = u64::MAX >> (64 - self.r_bits);
+
+        (remainder as usize, quotient & mask)
+    }
+
+    fn incr(&self, i: usize) -> usize {
+        (i + 1) & (self.slots.len() - 1)
+    }
+
+    fn decr(&self, i: usize) -> usize {
+        i.wrapping_sub(1) & (self.slots.len() - 1)
+    }
+
+    /// Returns the slot where the run of the canonical slot `fq` starts (or would start).
+    fn find_run_start(&self, fq: usize) -> usize {
+        let mut b = fq;
+        while self.slots[b].shifted {
+            b = self.decr(b);
+        }
+
+        let mut s = b;
+        while b != fq {
+            loop {
+                s = self.incr(s);
+                if !self.slots[s].continuation {
+                    break;
+                }
+            }
+
+            loop {
+                b = self.incr(b);
+                if self.slots[b].occupied {
+                    break;
+                }
+            }
+        }
+
+        s
+    }
+
+    /// Returns the slot holding the fingerprint, if any.
+    fn find(&self, fq: usize, fr: u64) -> Option<usize> {
+        if !self.slots[fq].occupied {
+            return None;
+        }
+
+        let mut s = self.find_run_start(fq);
+        loop {
+            if self.slots[s].remainder == fr {
+                return Some(s);
+            }
+
+            s = self.incr(s);
+            if !self.slots[s].continuation {
+                return None;
+            }
+        }
+    }
+
+    fn insert_fingerprint(&mut self, fq: usize, fr: u64) {
+        if self.slots[fq].is_empty() {
+            self.slots[fq] = Slot {
+                remainder: fr,
+                occupied: true,
+                ..Default::default()
+            };
+            return;
+        }
+
+        let was_occupied = self.slots[fq].occupied;
+        self.slots[fq].occupied = true;
+
+        let mut s = self.find_run_start(fq);
+        if was_occupied {
+            // Append the remainder at the end of the existing run.
+            loop {
+                s = self.incr(s);
+                if !self.slots[s].continuation {
+                    break;
+                }
+            }
+        }
+
+        // Shift the following remainders to the right until an empty slot is reached.
+        let mut remainder = fr;
+        let mut continuation = was_occupied;
+        let mut shifted = s != fq;
+        loop {
+            let slot = &mut self.slots[s];
+            let empty = slot.is_empty();
+
+            std::mem::swap(&mut slot.remainder, &mut remainder);
+            std::mem::swap(&mut slot.continuation, &mut continuation);
+            std::mem::swap(&mut slot.shifted, &mut shifted);
+
+            if empty {
+                break;
+            }
+
+            s = self.incr(s);
+            shifted = true;
+        }
+    }
+
+    /// Decodes the `(canonical slot, remainder)` pairs of the cluster starting at `start`.
+    fn cluster_entries(&self, start: usize) -> Vec<(usize, u64)> {
+        let mut entries = Vec::new();
+        let mut q = start;
+        let mut s = start;
+
+        loop {
+            if s != start && !self.slots[s].continuation {
+                loop {
+                    q = self.incr(q);
+                    if self.slots[q].occupied {
+                        break;
+                    }
+                }
+            }
+
+            entries.push((q, self.slots[s].remainder));
+
+            s = self.incr(s);
+            if s == start || self.slots[s].is_empty() {
+                break;
+            }
+        }
+
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{build_sip_hasher::BuildSipHasher, BuildPairHasher};
+
+    fn builder() -> BuildPairHasher<BuildSipHasher, BuildSipHasher> {
+        BuildPairHasher::new_with_keys((0, 0), (1, 1))
+    }
+
+    #[test]
+    fn no_false_negatives() {
+        let mut filter = QuotientFilter::new(builder(), 12, 16);
+        const ITEMS: u64 = 3000;
+
+        for item in 0..ITEMS {
+            assert!(filter.insert(&item));
+        }
+
+        assert_eq!(filter.len(), ITEMS as usize);
+        assert!((0..ITEMS).all(|item| filter.contains(&item)));
+    }
+
+    #[test]
+    fn full_filter() {
+        let mut filter = QuotientFilter::new(builder(), 3, 8);
+
+        for item in 0..8u64 {
+            assert!(filter.insert(&item));
+        }
+
+        assert!(!filter.insert(&8));
+        assert!((0..8u64).all(|item| filter.contains(&item)));
+    }
+
+    #[test]
+    fn delete() {
+        let mut filter = QuotientFilter::new(builder(), 12, 16);
+        const ITEMS: u64 = 3000;
+
+        for item in 0..ITEMS {
+            filter.insert(&item);
+        }
+
+        for item in (0..ITEMS).step_by(2) {
+            assert!(filter.delete(&item));
+        }
+
+        assert_eq!(filter.len(), ITEMS as usize / 2);
+        assert!((1..ITEMS).step_by(2).all(|item| filter.contains(&item)));
+
+        let false_positives = (0..ITEMS)
+            .step_by(2)
+            .filter(|item| filter.contains(item))
+            .count();
+        assert!(false_positives < 10);
+    }
+
+    #[test]
+    fn matches_fingerprint_multiset() {
+        // A small filter with many collisions, compared against a plain list of fingerprints.
+        let mut filter = QuotientFilter::new(builder(), 4, 4);
+        let mut model: Vec<(usize, u64)> = Vec::new();
+
+        let ops = (0..400u64).map(|i| (i * 7919 % 23, i % 3 != 0));
+        for (item, insert) in ops {
+            let fp = filter.fingerprint(&item);
+
+            if insert {
+                let inserted = filter.insert(&item);
+                assert_eq!(inserted, model.len() < filter.capacity());
+                if inserted {
+                    model.push(fp);
+                }
+            } else {
+                let deleted = filter.delete(&item);
+                let pos = model.iter().position(|&e| e == fp);
+                assert_eq!(deleted, pos.is_some());
+                if let Some(pos) = pos {
+                    model.swap_remove(pos);
+                }
+            }
+
+            assert_eq!(filter.len(), model.len());
+            for probe in 0..23u64 {
+                let fp = filter.fingerprint(&probe);
+                assert_eq!(filter.contains(&probe), model.contains(&fp));
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{Hash64, HasherExt, MultiHashIterator};
+use std::hash::Hasher;
+
+/// The word written into the inner hasher, after the data, to derive the second base hash.
+const REKEY_WORD: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// A [`Hasher`] which gives [`HasherExt::finish_iter`] to any [`Hasher`], e.g.
+/// [`std::hash::DefaultHasher`], so it can be plugged into the multi-hash machinery without a
+/// second hasher.
+///
+/// The first base hash `a` is the result of the inner hasher. The second base hash `b` is the
+/// result of the same hasher, *re-keyed* by writing a fixed 64-bit word into it after the data;
+/// since [`HasherExt::finish_iter`] consumes the adapter, the inner hasher does not need to be
+/// cloned. The sequence of hash values is then generated by a [`MultiHashIterator`] from `a`
+/// and `b`. Since both base hashes come from the same hasher, their quality is bounded by the
+/// quality of that hasher.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+/// use std::hash::{DefaultHasher, Hash, Hasher};
+///
+/// let mut hasher = RekeyHasher::new(DefaultHasher::new());
+/// "Hello world".hash(&mut hasher);
+///
+/// const HASHES_COUNT: usize = 10;
+/// let hashes = hasher.finish_iter().take(HASHES_COUNT).collect::<Vec<_>>();
+/// assert_eq!(hashes.len(), HASHES_COUNT);
+///```
+#[derive(Clone)]
+pub struct RekeyHasher<H> {
+    hasher: H,
+}
+
+impl<H> RekeyHasher<H> {
+    /// Creates a new adapter around the `hasher`.
+    pub fn new(hasher: H) -> Self {
+        Self { hasher }
+    }
+}
+
+impl<H> Hasher for RekeyHasher<H>
+where
+    H: Hasher,
+{
+    fn finish(&self) -> u64 {
+        self.hasher.finish()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.hasher.write(bytes);
+    }
+}
+
+impl<H> HasherExt for RekeyHasher<H>
+where
+    H: Hasher,
+{
+    fn finish_iter(self) -> impl Iterator<Item = Hash64> + Clone {
+        let a = self.hasher.finish();
+
+        let mut rekeyed = self.hasher;
+        rekeyed.write_u64(REKEY_WORD);
+        let b = rekeyed.finish();
+
+        MultiHashIterator::new(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{DefaultHasher, Hash};
+
+    #[test]
+    fn hash_finish_iter() {
+        let mut hasher = RekeyHasher::new(DefaultHasher::new());
+        "Hello world!".hash(&mut hasher);
+
+        let mut inner = DefaultHasher::new();
+        "Hello world!".hash(&mut inner);
+        let a = inner.finish();
+        inner.write_u64(REKEY_WORD);
+        let b = inner.finish();
+
+        assert_eq!(hasher.finish(), a);
+        assert!(hasher
+            .finish_iter()
+            .take(10)
+            .eq(MultiHashIterator::new(a, b).take(10)));
+    }
+
+    #[test]
+    fn hasher_without_clone() {
+        /// A hasher which does not implement `Clone`.
+        struct Sum(u64);
+
+        impl Hasher for Sum {
+            fn finish(&self) -> u64 {
+                self.0
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                self.0 += bytes.iter().map(|&byte| u64::from(byte)).sum::<u64>();
+            }
+        }
+
+        let mut hasher = RekeyHasher::new(Sum(0));
+        hasher.write(&[1, 2, 3]);
+
+        let rekeyed = 6 + REKEY_WORD
+            .to_ne_bytes()
+            .iter()
+            .map(|&byte| u64::from(byte))
+            .sum::<u64>();
+        assert!(hasher
+            .finish_iter()
+            .take(10)
+            .eq(MultiHashIterator::new(6, rekeyed).take(10)));
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{BuildHasherExt, Hash64, HasherExt};
+use std::hash::Hash;
+
+/// Computes the rendezvous weight of a node for a given key.
+fn rendezvous_weight<B, K, N>(builder: &B, key: &K, node: &N) -> Hash64
+where
+    B: BuildHasherExt,
+    B::Hasher: HasherExt,
+    K: Hash + ?Sized,
+    N: Hash,
+{
+    builder
+        .hashes_one((key, node))
+        .next()
+        .expect("the hash sequence is infinite")
+}
+
+/// Selects a node for a key using rendezvous (highest random weight) hashing with bounded loads.
+/// The nodes are ranked by the hash of the `(key, node)` pair and the highest ranked node whose
+/// current load is below `cap` is returned. If all the nodes reached the `cap`, the highest
+/// ranked node is returned.
+///
+/// The `loads` slice holds the current load of each node and it is not updated by the function.
+///
+/// # Panics
+///
+/// Panics if `nodes` is empty or if `loads` and `nodes` have different lengths.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+///
+/// let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+/// let nodes = ["node-a", "node-b", "node-c"];
+/// let mut loads = [0; 3];
+///
+/// for key in 0..30 {
+///     let node = bounded_rendezvous_select(&builder, &key, &nodes, &loads, 10);
+///     loads[node] += 1;
+/// }
+///
+/// assert_eq!(loads, [10, 10, 10]);
+///```
+pub fn bounded_rendezvous_select<B, K, N>(
+    builder: &B,
+    key: &K,
+    nodes: &[N],
+    loads: &[usize],
+    cap: usize,
+) -> usize
+where
+    B: BuildHasherExt,
+    B::Hasher: HasherExt,
+    K: Hash + ?Sized,
+    N: Hash,
+{
+    assert!(!nodes.is_empty(), "there are no nodes to select from");
+    assert_eq!(nodes.len(), loads.len(), "each node must have a load");
+
+    let mut ranked = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (rendezvous_weight(builder, key, node), i))
+        .collect::<Vec<_>>();
+    ranked.sort_unstable_by(|a, b| b.cmp(a));
+
+    ranked
+        .iter()
+        .map(|&(_, i)| i)
+        .find(|&i| loads[i] < cap)
+        .unwrap_or(ranked[0].1)
+}
+
+/// Selects a node for a key using weighted rendezvous hashing, for clusters where the nodes
+/// have different capacities. Each node is scored with `-weight / ln(u)`, where `u` is the hash
+/// of the `(key, node)` pair mapped into `[0, 1)`, and the node with the highest score is returned.
+/// A node receives a share of the keys proportional to its weight.
+///
+/// The weights must be positive and finite.
+///
+/// # Panics
+///
+/// Panics if `nodes` is empty.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+///
+/// let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+/// let nodes = [("small", 1.0), ("large", 3.0)];
+///
+/// let node = weighted_rendezvous_select(&builder, "key", &nodes);
+/// assert!(node < nodes.len());
+///```
+pub fn weighted_rendezvous_select<B, K, N>(builder: &B, key: &K, nodes: &[(N, f64)]) -> usize
+where
+    B: BuildHasherExt,
+    B::Hasher: HasherExt,
+    K: Hash + ?Sized,
+    N: Hash,
+{
+    assert!(!nodes.is_empty(), "there are no nodes to select from");
+
+    nodes
+        .iter()
+        .map(|(node, weight)| {
+            let u = rendezvous_weight(builder, key, node).as_f64_unit();
+            -weight / u.ln()
+        })
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(i, _)| i)
+        .expect("there is at least one node")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BuildPairHasher;
+
+    #[test]
+    fn unbounded_is_rendezvous() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let nodes = ["a", "b", "c", "d"];
+        let loads = [0; 4];
+
+        for key in 0..100 {
+            let expected = nodes
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, node)| rendezvous_weight(&builder, &key, *node))
+                .map(|(i, _)| i)
+                .unwrap();
+
+            let selected = bounded_rendezvous_select(&builder, &key, &nodes, &loads, usize::MAX);
+            assert_eq!(selected, expected);
+        }
+    }
+
+    #[test]
+    fn bounded_loads() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let nodes = ["a", "b", "c", "d", "e"];
+        let mut loads = [0; 5];
+
+        // A skewed key distribution, where the small keys are requested many times.
+        let keys = (1..=40u64).flat_map(|key| std::iter::repeat_n(key, (200 / key) as usize));
+        let total = keys.clone().count();
+        let cap = (total / nodes.len()) * 5 / 4;
+
+        for key in keys {
+            let node = bounded_rendezvous_select(&builder, &key, &nodes, &loads, cap);
+            loads[node] += 1;
+        }
+
+        assert_eq!(loads.iter().sum::<usize>(), total);
+        assert!(loads.iter().all(|&load| load <= cap));
+    }
+
+    #[test]
+    fn all_nodes_full() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let nodes = ["a", "b", "c"];
+        let loads = [5; 3];
+
+        let selected = bounded_rendezvous_select(&builder, &42, &nodes, &loads, 5);
+        let unbounded = bounded_rendezvous_select(&builder, &42, &nodes, &[0; 3], 5);
+        assert_eq!(selected, unbounded);
+    }
+
+    #[test]
+    fn weighted_proportions() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let nodes = [("a", 1.0), ("b", 2.0), ("c", 4.0)];
+        const KEYS: usize = 7000;
+
+        let mut counts = [0usize; 3];
+        for key in 0..KEYS {
+            counts[weighted_rendezvous_select(&builder, &key, &nodes)] += 1;
+        }
+
+        let total_weight = nodes.iter().map(|(_, w)| w).sum::<f64>();
+        for ((_, weight), count) in nodes.iter().zip(counts) {
+            let expected = KEYS as f64 * weight / total_weight;
+            let error = (count as f64 - expected).abs() / expected;
+            assert!(error < 0.15, "{count} keys instead of {expected}");
+        }
+    }
+}
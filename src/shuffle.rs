@@ -0,0 +1,82 @@
+use crate::{BuildHasherExt, HasherExt};
+
+/// Shuffles a slice in place, deterministically for a given builder. It performs a Fisher-Yates
+/// shuffle where the swap index for the position `i` is derived from the first hash value of `i`,
+/// so the same builder always produces the same permutation, e.g. for reproducible test ordering.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+///
+/// let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+///
+/// let mut items1 = [1, 2, 3, 4, 5];
+/// let mut items2 = [1, 2, 3, 4, 5];
+/// stable_shuffle(&builder, &mut items1);
+/// stable_shuffle(&builder, &mut items2);
+///
+/// assert_eq!(items1, items2);
+///```
+pub fn stable_shuffle<B, T>(builder: &B, slice: &mut [T])
+where
+    B: BuildHasherExt,
+    B::Hasher: HasherExt,
+{
+    for i in (1..slice.len()).rev() {
+        let h = builder
+            .hashes_one(i)
+            .next()
+            .expect("the hash sequence is infinite");
+        let j = (u64::from(h) % (i as u64 + 1)) as usize;
+
+        slice.swap(i, j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BuildPairHasher;
+
+    #[test]
+    fn deterministic() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+
+        let mut items1 = (0..100).collect::<Vec<_>>();
+        let mut items2 = (0..100).collect::<Vec<_>>();
+        stable_shuffle(&builder, &mut items1);
+        stable_shuffle(&builder, &mut items2);
+
+        assert_eq!(items1, items2);
+        assert_ne!(items1, (0..100).collect::<Vec<_>>());
+
+        items1.sort();
+        assert_eq!(items1, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn different_builders() {
+        let builder1 = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let builder2 = BuildPairHasher::new_with_keys((2, 2), (3, 3));
+
+        let mut items1 = (0..100).collect::<Vec<_>>();
+        let mut items2 = (0..100).collect::<Vec<_>>();
+        stable_shuffle(&builder1, &mut items1);
+        stable_shuffle(&builder2, &mut items2);
+
+        assert_ne!(items1, items2);
+    }
+
+    #[test]
+    fn small_slices() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+
+        let mut empty: [u8; 0] = [];
+        stable_shuffle(&builder, &mut empty);
+
+        let mut one = [1];
+        stable_shuffle(&builder, &mut one);
+        assert_eq!(one, [1]);
+    }
+}
@@ -0,0 +1,74 @@
+//! Reusable conformance checks for [`BuildHasherExt`] implementations.
+//!
+//! The module is available under the `testutil` feature, so downstream crates can
+//! run the same checks against their own builders from their test suites.
+//!
+//! # Example
+//!
+//!```
+//! use aabel_multihash_rs::{testutil, BuildPairHasher};
+//!
+//! let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+//! let item = "Hello world!";
+//!
+//! testutil::assert_deterministic(&builder, &item);
+//! testutil::assert_nonzero_stream(&builder, &item, 10);
+//!```
+use crate::{BuildHasherExt, Hash64, HasherExt};
+use std::hash::Hash;
+
+/// The number of hash values compared by [`assert_deterministic`].
+const DETERMINISTIC_COUNT: usize = 32;
+
+/// Asserts that hashing the same item twice with the same builder produces the same sequence of hash values.
+pub fn assert_deterministic<B, T>(builder: &B, item: &T)
+where
+    B: BuildHasherExt,
+    B::Hasher: HasherExt,
+    T: Hash + ?Sized,
+{
+    let hashes1 = builder
+        .hashes_one(item)
+        .take(DETERMINISTIC_COUNT)
+        .collect::<Vec<_>>();
+    let hashes2 = builder
+        .hashes_one(item)
+        .take(DETERMINISTIC_COUNT)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        hashes1, hashes2,
+        "the builder produced different sequences for the same item"
+    );
+}
+
+/// Asserts that the first `n` hash values generated for the item are all different than zero.
+pub fn assert_nonzero_stream<B, T>(builder: &B, item: &T, n: usize)
+where
+    B: BuildHasherExt,
+    B::Hasher: HasherExt,
+    T: Hash + ?Sized,
+{
+    let hashes = builder.hashes_one(item).take(n).collect::<Vec<_>>();
+
+    assert_eq!(hashes.len(), n, "the sequence has less than {n} values");
+    assert!(
+        hashes.iter().all(|h| h != &Hash64::from(0)),
+        "the sequence contains a zero hash value"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BuildPairHasher;
+
+    #[test]
+    fn build_pair_hasher_conformance() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let item = "Hello world!";
+
+        assert_deterministic(&builder, &item);
+        assert_nonzero_stream(&builder, &item, 10);
+    }
+}
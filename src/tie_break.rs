@@ -0,0 +1,65 @@
+use crate::{BuildHasherExt, HasherExt};
+use std::{cmp::Ordering, hash::Hash};
+
+/// Orders two items by their first hash values. It can be used to break the ties between items
+/// which have equal sort keys in a deterministic way, e.g. `a.cmp(&b).then_with(|| tie_break(...))`.
+///
+/// For distinct items the ordering is a strict total order, unless their first hash values collide.
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+///
+/// let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+///
+/// let mut items = vec![("b", 1), ("a", 1), ("c", 0)];
+/// items.sort_by(|x, y| x.1.cmp(&y.1).then_with(|| tie_break(&builder, &x.0, &y.0)));
+/// assert_eq!(items[0], ("c", 0));
+///```
+pub fn tie_break<T, B>(builder: &B, a: &T, b: &T) -> Ordering
+where
+    T: Hash + ?Sized,
+    B: BuildHasherExt,
+    B::Hasher: HasherExt,
+{
+    let ha = builder.hashes_one(a).next();
+    let hb = builder.hashes_one(b).next();
+    ha.cmp(&hb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BuildPairHasher;
+
+    #[test]
+    fn strict_total_order() {
+        let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1));
+        let items = (0..200).collect::<Vec<u32>>();
+
+        for a in &items {
+            assert_eq!(tie_break(&builder, a, a), Ordering::Equal);
+
+            for b in &items {
+                let ab = tie_break(&builder, a, b);
+                let ba = tie_break(&builder, b, a);
+
+                assert_eq!(ab, ba.reverse());
+                if a != b {
+                    assert_ne!(ab, Ordering::Equal);
+                }
+            }
+        }
+
+        let mut sorted = items.clone();
+        sorted.sort_by(|a, b| tie_break(&builder, a, b));
+        assert!(sorted
+            .windows(2)
+            .all(|w| tie_break(&builder, &w[0], &w[1]) == Ordering::Less));
+
+        let mut sorted_again = items.into_iter().rev().collect::<Vec<_>>();
+        sorted_again.sort_by(|a, b| tie_break(&builder, a, b));
+        assert_eq!(sorted, sorted_again);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{Hash64, HasherExt, TripleHashing};
+use std::hash::Hasher;
+
+/// A [`Hasher`] which combines three [`Hasher`] instances for triple hashing. With `a`, `b`
+/// and `c` the results of the three hashers, the `i`-th generated hash value is
+/// `a + i * b + i * (i - 1) / 2 * c` (wrapping), the sequence of [`TripleHashing::sequence_with`].
+///
+/// # Example
+///
+///```
+/// use aabel_multihash_rs::*;
+/// use std::hash::{BuildHasher, Hash};
+///
+/// let mut hasher = BuildTripleHasher::new_with_keys((0, 0), (1, 1), (2, 2)).build_hasher();
+///
+/// let item = "Hello world";
+/// item.hash(&mut hasher);
+///
+/// const HASHES_COUNT: usize = 10;
+/// let hashes = hasher.finish_iter().take(HASHES_COUNT).collect::<Vec<_>>();
+/// assert!(hashes.into_iter().all(|h| h != Hash64::from(0)));
+///```
+pub struct TripleHasher<H1, H2, H3> {
+    hasher1: H1,
+    hasher2: H2,
+    hasher3: H3,
+}
+
+impl<H1, H2, H3> TripleHasher<H1, H2, H3> {
+    pub(crate) fn new(hasher1: H1, hasher2: H2, hasher3: H3) -> Self {
+        Self {
+            hasher1,
+            hasher2,
+            hasher3,
+        }
+    }
+}
+
+impl<H1, H2, H3> Hasher for TripleHasher<H1, H2, H3>
+where
+    H1: Hasher,
+    H2: Hasher,
+    H3: Hasher,
+{
+    fn finish(&self) -> u64 {
+        let a = self.hasher1.finish();
+        let b = self.hasher2.finish();
+        let c = self.hasher3.finish();
+        a.wrapping_add(b).wrapping_add(c)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.hasher1.write(bytes);
+        self.hasher2.write(bytes);
+        self.hasher3.write(bytes);
+    }
+}
+
+impl<H1, H2, H3> HasherExt for TripleHasher<H1, H2, H3>
+where
+    H1: Hasher,
+    H2: Hasher,
+    H3: Hasher,
+{
+    fn finish_iter(self) -> impl Iterator<Item = Hash64> + Clone {
+        let a = self.hasher1.finish();
+        let b = self.hasher2.finish();
+        let c = self.hasher3.finish();
+
+        TripleHashing::sequence_with(a, b, c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use siphasher::sip::SipHasher;
+    use std::hash::Hash;
+
+    fn build() -> TripleHasher<SipHasher, SipHasher, SipHasher> {
+        let hasher1 = SipHasher::new_with_keys(0, 0);
+        let hasher2 = SipHasher::new_with_keys(1, 1);
+        let hasher3 = SipHasher::new_with_keys(2, 2);
+        TripleHasher::new(hasher1, hasher2, hasher3)
+    }
+
+    #[test]
+    fn hash_finish() {
+        let mut hasher = build();
+        "Hello world!".hash(&mut hasher);
+
+        assert_ne!(hasher.finish(), 0);
+    }
+
+    #[test]
+    fn quadratic_recurrence() {
+        let mut hasher = build();
+        "Hello world!".hash(&mut hasher);
+
+        let a = hasher.hasher1.finish();
+        let b = hasher.hasher2.finish();
+        let c = hasher.hasher3.finish();
+
+        let hashes = hasher.finish_iter().take(20).map(u64::from);
+        for (i, h) in (0u64..).zip(hashes) {
+            let expected = a
+                .wrapping_add(i.wrapping_mul(b))
+                .wrapping_add((i * i.saturating_sub(1) / 2).wrapping_mul(c));
+            assert_eq!(h, expected);
+        }
+    }
+}
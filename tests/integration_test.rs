@@ -20,3 +20,17 @@ fn get_hashes() {
         .collect::<Vec<_>>();
     assert!(hashes.into_iter().all(|hash| hash != Hash64::from(0)));
 }
+
+#[test]
+fn long_input_and_stream() {
+    // Regression guard for the debug-build overflow checks on the recurrence and the write path.
+    let builder = BuildPairHasher::new_with_keys((0, 0), (1, 1)).with_mix_seed(u64::MAX);
+    let buffer = vec![0xa5u8; 10 * 1024 * 1024];
+    const HASHES_COUNT: usize = 1_000_000;
+
+    let count = builder.hashes_one(&buffer).take(HASHES_COUNT).count();
+    assert_eq!(count, HASHES_COUNT);
+
+    let count = builder.hashes_bytes(&buffer).take(HASHES_COUNT).count();
+    assert_eq!(count, HASHES_COUNT);
+}